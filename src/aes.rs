@@ -0,0 +1,344 @@
+//! Low-level AES in CTR and CBC modes.
+//!
+//! **Neither mode provides integrity.** Ciphertexts can be modified without
+//! detection, and CBC decryption with padding checks is a classic padding
+//! oracle if failures are observable by an attacker. These primitives exist
+//! for interoperating with existing formats; new designs should use an AEAD.
+
+use std::os::raw::c_int;
+
+use zeroize::Zeroizing;
+
+use crate::error::WolfCryptError;
+
+/// The AES block size in bytes.
+pub const BLOCK_SIZE: usize = 16;
+
+/// Errors that can occur during AES operations.
+#[derive(Debug, thiserror::Error)]
+pub enum AesError {
+    /// Keys must be 16, 24 or 32 bytes long.
+    #[error("invalid AES key length {0}")]
+    InvalidKeyLength(usize),
+    /// Unpadded CBC input, and all CBC ciphertext, must be a multiple of
+    /// [`BLOCK_SIZE`] bytes long.
+    #[error("input length {0} is not a multiple of the AES block size")]
+    InvalidLength(usize),
+    /// The decrypted plaintext did not end in valid PKCS#7 padding.
+    #[error("invalid padding")]
+    BadPadding,
+    /// wolfCrypt reported an error.
+    #[error(transparent)]
+    WolfCrypt(#[from] WolfCryptError),
+}
+
+/// How CBC plaintext is padded to a multiple of the block size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// PKCS#7 padding, always adding between 1 and [`BLOCK_SIZE`] bytes.
+    Pkcs7,
+    /// No padding. The input must already be block aligned.
+    None,
+}
+
+/// AES in counter mode.
+///
+/// The keystream position carries over between calls, so a message can be
+/// processed in pieces. Encryption and decryption are the same operation.
+pub struct AesCtr(Aes);
+
+impl AesCtr {
+    /// Creates a CTR instance with the given key and initial counter block.
+    ///
+    /// A key and counter pair must never be used for more than one message.
+    pub fn new(key: &[u8], iv: &[u8; BLOCK_SIZE]) -> Result<Self, AesError> {
+        let mut aes = Aes::new(key)?;
+
+        // SAFETY: `aes.0` is initialized, and `key` and `iv` are valid for
+        // their lengths. CTR mode only ever uses the forward cipher.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_AesSetKeyDirect(
+                &mut *aes.0,
+                key.as_ptr(),
+                key.len() as u32,
+                iv.as_ptr(),
+                wolfssl_sys::AES_ENCRYPTION as c_int,
+            )
+        })?;
+
+        Ok(Self(aes))
+    }
+
+    /// XORs the next `buf.len()` bytes of keystream into `buf`, in place.
+    pub fn apply_keystream(&mut self, buf: &mut [u8]) -> Result<(), AesError> {
+        for chunk in buf.chunks_mut(u32::MAX as usize) {
+            let ptr = chunk.as_mut_ptr();
+
+            // SAFETY: `self.0` is keyed, and wolfCrypt supports the input and
+            // output overlapping exactly.
+            WolfCryptError::check(unsafe {
+                wolfssl_sys::wc_AesCtrEncrypt(&mut *self.0 .0, ptr, ptr, chunk.len() as u32)
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for AesCtr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AesCtr").finish_non_exhaustive()
+    }
+}
+
+/// Encrypts `plaintext` with AES-CBC.
+///
+/// `iv` must be unpredictable, i.e. freshly generated for every message.
+pub fn cbc_encrypt(
+    key: &[u8],
+    iv: &[u8; BLOCK_SIZE],
+    plaintext: &[u8],
+    padding: Padding,
+) -> Result<Vec<u8>, AesError> {
+    let mut buf = Zeroizing::new(plaintext.to_vec());
+    match padding {
+        Padding::Pkcs7 => {
+            let pad = BLOCK_SIZE - plaintext.len() % BLOCK_SIZE;
+            buf.resize(plaintext.len() + pad, pad as u8);
+        }
+        Padding::None if !plaintext.len().is_multiple_of(BLOCK_SIZE) => {
+            return Err(AesError::InvalidLength(plaintext.len()));
+        }
+        Padding::None => {}
+    }
+    if u32::try_from(buf.len()).is_err() {
+        return Err(AesError::InvalidLength(plaintext.len()));
+    }
+
+    let mut aes = Aes::new(key)?;
+    aes.set_cbc_key(key, iv, wolfssl_sys::AES_ENCRYPTION as c_int)?;
+
+    let ptr = buf.as_mut_ptr();
+
+    // SAFETY: `aes.0` is keyed, `buf` is block aligned, and wolfCrypt
+    // supports the input and output overlapping exactly.
+    WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_AesCbcEncrypt(&mut *aes.0, ptr, ptr, buf.len() as u32)
+    })?;
+
+    Ok(std::mem::take(&mut *buf))
+}
+
+/// Decrypts `ciphertext` with AES-CBC.
+///
+/// With [`Padding::Pkcs7`], returns [`AesError::BadPadding`] if the padding
+/// is malformed. See the module documentation before exposing that outcome
+/// to a peer.
+pub fn cbc_decrypt(
+    key: &[u8],
+    iv: &[u8; BLOCK_SIZE],
+    ciphertext: &[u8],
+    padding: Padding,
+) -> Result<Vec<u8>, AesError> {
+    if !ciphertext.len().is_multiple_of(BLOCK_SIZE) || u32::try_from(ciphertext.len()).is_err() {
+        return Err(AesError::InvalidLength(ciphertext.len()));
+    }
+
+    let mut aes = Aes::new(key)?;
+    aes.set_cbc_key(key, iv, wolfssl_sys::AES_DECRYPTION as c_int)?;
+
+    // Zeroized unless decryption succeeds and ownership passes to the caller.
+    let mut buf = Zeroizing::new(ciphertext.to_vec());
+    let ptr = buf.as_mut_ptr();
+
+    // SAFETY: `aes.0` is keyed, `buf` is block aligned, and wolfCrypt
+    // supports the input and output overlapping exactly.
+    WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_AesCbcDecrypt(&mut *aes.0, ptr, ptr, buf.len() as u32)
+    })?;
+
+    if padding == Padding::Pkcs7 {
+        let pad = match buf.last() {
+            Some(&pad) if (1..=BLOCK_SIZE).contains(&(pad as usize)) => pad,
+            _ => return Err(AesError::BadPadding),
+        };
+        let start = buf.len() - pad as usize;
+        if buf[start..].iter().any(|&b| b != pad) {
+            return Err(AesError::BadPadding);
+        }
+        buf.truncate(start);
+    }
+
+    Ok(std::mem::take(&mut *buf))
+}
+
+/// An initialized wolfCrypt `Aes` context.
+///
+/// The context is boxed so that the key schedule is set up in its final
+/// location, rather than left behind in stack copies when the value moves.
+struct Aes(Box<wolfssl_sys::Aes>);
+
+impl Aes {
+    fn new(key: &[u8]) -> Result<Self, AesError> {
+        if !matches!(key.len(), 16 | 24 | 32) {
+            return Err(AesError::InvalidKeyLength(key.len()));
+        }
+
+        let mut aes = Box::<wolfssl_sys::Aes>::new_uninit();
+
+        // SAFETY: `aes` is writable for an `Aes`. A null heap hint and
+        // `INVALID_DEVID` select the default software implementation.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_AesInit(
+                aes.as_mut_ptr(),
+                std::ptr::null_mut(),
+                wolfssl_sys::INVALID_DEVID,
+            )
+        })?;
+
+        // SAFETY: `wc_AesInit` succeeded.
+        Ok(Self(unsafe { aes.assume_init() }))
+    }
+
+    fn set_cbc_key(
+        &mut self,
+        key: &[u8],
+        iv: &[u8; BLOCK_SIZE],
+        dir: c_int,
+    ) -> Result<(), WolfCryptError> {
+        // SAFETY: `self.0` is initialized, and `key` and `iv` are valid for
+        // their lengths.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_AesSetKey(
+                &mut *self.0,
+                key.as_ptr(),
+                key.len() as u32,
+                iv.as_ptr(),
+                dir,
+            )
+        })?;
+        Ok(())
+    }
+}
+
+impl Drop for Aes {
+    fn drop(&mut self) {
+        // SAFETY: `self.0` is initialized and not used again. It is plain old
+        // data, so it can be scrubbed of the key schedule once freed.
+        unsafe {
+            wolfssl_sys::wc_AesFree(&mut *self.0);
+            zeroize::zeroize_flat_type(&mut *self.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NIST SP 800-38A appendix F.
+    const KEY_128: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+    const KEY_256: &str = "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+    const PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                             30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+    const CBC_IV: [u8; BLOCK_SIZE] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f,
+    ];
+    const CTR_IV: [u8; BLOCK_SIZE] = [
+        0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe,
+        0xff,
+    ];
+
+    /// F.2.1, F.2.5, F.5.1 and F.5.5.
+    const VECTORS: [(&str, &str, &str); 2] = [
+        (
+            KEY_128,
+            "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2\
+             73bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7",
+            "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+             5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee",
+        ),
+        (
+            KEY_256,
+            "f58c4c04d6e5f1ba779eabfb5f7bfbd69cfc4e967edb808d679f777bc6702c7d\
+             39f23369a9d9bacfa530e26304231461b2eb05e2c39be9fcda6c19078c6a9d1b",
+            "601ec313775789a5b7a7f504bbf3d228f443e3ca4d62b59aca84e990cacaf5c5\
+             2b0930daa23de94ce87017ba2d84988ddfc9c58db67aada613c2dd08457941a6",
+        ),
+    ];
+
+    #[test]
+    fn cbc_known_answers() {
+        let plaintext = hex::decode(PLAINTEXT).unwrap();
+
+        for (key, cbc, _) in VECTORS {
+            let key = hex::decode(key).unwrap();
+            let ciphertext = cbc_encrypt(&key, &CBC_IV, &plaintext, Padding::None).unwrap();
+            assert_eq!(hex::encode(&ciphertext), cbc);
+
+            let decrypted = cbc_decrypt(&key, &CBC_IV, &ciphertext, Padding::None).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+    }
+
+    #[test]
+    fn ctr_known_answers() {
+        let plaintext = hex::decode(PLAINTEXT).unwrap();
+
+        for (key, _, ctr) in VECTORS {
+            let key = hex::decode(key).unwrap();
+
+            // Uneven pieces exercise the carried over keystream position.
+            let mut buf = plaintext.clone();
+            let mut aes = AesCtr::new(&key, &CTR_IV).unwrap();
+            let (head, tail) = buf.split_at_mut(5);
+            aes.apply_keystream(head).unwrap();
+            aes.apply_keystream(tail).unwrap();
+            assert_eq!(hex::encode(&buf), ctr);
+
+            let mut aes = AesCtr::new(&key, &CTR_IV).unwrap();
+            aes.apply_keystream(&mut buf).unwrap();
+            assert_eq!(buf, plaintext);
+        }
+    }
+
+    #[test]
+    fn cbc_pkcs7_padding() {
+        let key = hex::decode(KEY_128).unwrap();
+
+        for len in [0, 1, 15, 16, 17] {
+            let plaintext = vec![0x5a; len];
+            let ciphertext = cbc_encrypt(&key, &CBC_IV, &plaintext, Padding::Pkcs7).unwrap();
+            assert_eq!(ciphertext.len(), (len / BLOCK_SIZE + 1) * BLOCK_SIZE);
+
+            let decrypted = cbc_decrypt(&key, &CBC_IV, &ciphertext, Padding::Pkcs7).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+
+        // Unpadded zeroes decrypt to a final byte of 0, which is not valid
+        // PKCS#7 padding.
+        let ciphertext = cbc_encrypt(&key, &CBC_IV, &[0; BLOCK_SIZE], Padding::None).unwrap();
+        assert!(matches!(
+            cbc_decrypt(&key, &CBC_IV, &ciphertext, Padding::Pkcs7),
+            Err(AesError::BadPadding)
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_lengths() {
+        let key = hex::decode(KEY_128).unwrap();
+
+        assert!(matches!(
+            AesCtr::new(&key[..15], &CTR_IV),
+            Err(AesError::InvalidKeyLength(15))
+        ));
+        assert!(matches!(
+            cbc_encrypt(&key, &CBC_IV, &[0; 15], Padding::None),
+            Err(AesError::InvalidLength(15))
+        ));
+        assert!(matches!(
+            cbc_decrypt(&key, &CBC_IV, &[0; 17], Padding::Pkcs7),
+            Err(AesError::InvalidLength(17))
+        ));
+    }
+}
@@ -0,0 +1,18 @@
+//! Constant-time operations.
+
+/// Returns whether `a` and `b` are equal, in time that depends only on their
+/// lengths.
+///
+/// Use this rather than `==` when comparing a secret, such as a MAC tag,
+/// against attacker supplied input. Lengths are not secret; inputs of
+/// different lengths compare unequal immediately.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    // SAFETY: Both buffers are valid for `a.len()` bytes.
+    unsafe {
+        wolfssl_sys::wolfSSL_CRYPTO_memcmp(a.as_ptr().cast(), b.as_ptr().cast(), a.len()) == 0
+    }
+}
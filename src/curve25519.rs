@@ -0,0 +1,291 @@
+//! X25519 key agreement (RFC 7748).
+//!
+//! Private keys and shared secrets are zeroized when dropped.
+
+use std::mem::MaybeUninit;
+use std::os::raw::c_int;
+
+use zeroize::Zeroizing;
+
+use crate::ct::constant_time_eq;
+use crate::error::WolfCryptError;
+use crate::rng::WolfRng;
+
+/// The length in bytes of X25519 keys and shared secrets.
+pub const KEY_LEN: usize = 32;
+
+/// An X25519 private key.
+#[derive(Clone)]
+pub struct X25519PrivateKey(Zeroizing<[u8; KEY_LEN]>);
+
+impl X25519PrivateKey {
+    /// Generates a new private key.
+    pub fn generate(rng: &mut WolfRng) -> Result<Self, WolfCryptError> {
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+
+        // SAFETY: `rng` is an initialized RNG and `key` is writable for
+        // `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve25519_make_priv(
+                rng.as_mut_ptr(),
+                KEY_LEN as c_int,
+                key.as_mut_ptr(),
+            )
+        })?;
+
+        Ok(Self(key))
+    }
+
+    /// Wraps an existing private key, in the RFC 7748 (little-endian)
+    /// encoding.
+    ///
+    /// The key is clamped as RFC 7748 section 5 describes, which wolfCrypt
+    /// requires and which does not change the keys it agrees on, so
+    /// [`Self::to_bytes`] may not return `bytes` unchanged.
+    pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Self {
+        let mut key = Zeroizing::new(bytes);
+        key[0] &= 248;
+        key[KEY_LEN - 1] &= 127;
+        key[KEY_LEN - 1] |= 64;
+        Self(key)
+    }
+
+    /// Returns the private key in the RFC 7748 (little-endian) encoding.
+    pub fn to_bytes(&self) -> Zeroizing<[u8; KEY_LEN]> {
+        self.0.clone()
+    }
+
+    /// Computes the matching public key.
+    pub fn public_key(&self) -> Result<X25519PublicKey, WolfCryptError> {
+        let mut public = [0u8; KEY_LEN];
+
+        // SAFETY: Both buffers are valid for `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve25519_make_pub(
+                KEY_LEN as c_int,
+                public.as_mut_ptr(),
+                KEY_LEN as c_int,
+                self.0.as_ptr(),
+            )
+        })?;
+
+        Ok(X25519PublicKey(public))
+    }
+
+    /// Computes the shared secret with `peer`.
+    ///
+    /// Fails if the result is the all-zero value, which indicates a
+    /// low-order peer public key.
+    pub fn diffie_hellman(&self, peer: &X25519PublicKey) -> Result<SharedSecret, WolfCryptError> {
+        let mut private = Curve25519Key::new()?;
+        let mut public = Curve25519Key::new()?;
+
+        // SAFETY: `private.0` is initialized and `self.0` is valid for
+        // `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve25519_import_private_ex(
+                self.0.as_ptr(),
+                KEY_LEN as u32,
+                &mut private.0,
+                wolfssl_sys::EC25519_LITTLE_ENDIAN as c_int,
+            )
+        })?;
+
+        // SAFETY: `public.0` is initialized and `peer.0` is valid for
+        // `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve25519_import_public_ex(
+                peer.0.as_ptr(),
+                KEY_LEN as u32,
+                &mut public.0,
+                wolfssl_sys::EC25519_LITTLE_ENDIAN as c_int,
+            )
+        })?;
+
+        let mut secret = Zeroizing::new([0u8; KEY_LEN]);
+        let mut secret_len = KEY_LEN as u32;
+
+        // SAFETY: Both keys were imported above, and `secret` is writable for
+        // `secret_len` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve25519_shared_secret_ex(
+                &mut private.0,
+                &mut public.0,
+                secret.as_mut_ptr(),
+                &mut secret_len,
+                wolfssl_sys::EC25519_LITTLE_ENDIAN as c_int,
+            )
+        })?;
+
+        // Not every wolfSSL build checks this itself.
+        if constant_time_eq(&*secret, &[0u8; KEY_LEN]) {
+            return Err(WolfCryptError::ECC_OUT_OF_RANGE_E);
+        }
+
+        Ok(SharedSecret(secret))
+    }
+
+    /// Loads the key pair into a wolfCrypt object, for APIs that take a
+    /// `curve25519_key`.
+    ///
+    /// The object is boxed so that the key is imported in its final
+    /// location.
+    #[cfg(feature = "hpke")]
+    pub(crate) fn load(&self) -> Result<Box<Curve25519Key>, WolfCryptError> {
+        let public = self.public_key()?;
+        let mut key = Box::new(Curve25519Key::new()?);
+
+        // SAFETY: `key.0` is initialized and both halves are valid for
+        // `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve25519_import_private_raw_ex(
+                self.0.as_ptr(),
+                KEY_LEN as u32,
+                public.0.as_ptr(),
+                KEY_LEN as u32,
+                &mut key.0,
+                wolfssl_sys::EC25519_LITTLE_ENDIAN as c_int,
+            )
+        })?;
+
+        Ok(key)
+    }
+}
+
+impl std::fmt::Debug for X25519PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("X25519PrivateKey").finish_non_exhaustive()
+    }
+}
+
+/// An X25519 public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct X25519PublicKey([u8; KEY_LEN]);
+
+impl X25519PublicKey {
+    /// Wraps a public key in the RFC 7748 (little-endian) encoding.
+    pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the public key in the RFC 7748 (little-endian) encoding.
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+}
+
+/// The output of an X25519 key agreement.
+///
+/// This should be passed through a KDF (e.g. [`crate::hkdf`]) rather than
+/// used as a key directly.
+pub struct SharedSecret(Zeroizing<[u8; KEY_LEN]>);
+
+impl SharedSecret {
+    /// Returns the raw shared secret.
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for SharedSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedSecret").finish_non_exhaustive()
+    }
+}
+
+/// A wolfCrypt key object; freeing it scrubs the key material.
+///
+/// This is transparent so that a pointer to it can be handed to wolfCrypt
+/// as a `curve25519_key`.
+#[repr(transparent)]
+pub(crate) struct Curve25519Key(wolfssl_sys::curve25519_key);
+
+impl Curve25519Key {
+    fn new() -> Result<Self, WolfCryptError> {
+        let mut key = MaybeUninit::<wolfssl_sys::curve25519_key>::uninit();
+
+        // SAFETY: `key` is writable for a `curve25519_key`.
+        WolfCryptError::check(unsafe { wolfssl_sys::wc_curve25519_init(key.as_mut_ptr()) })?;
+
+        // SAFETY: `wc_curve25519_init` succeeded.
+        Ok(Self(unsafe { key.assume_init() }))
+    }
+}
+
+impl Drop for Curve25519Key {
+    fn drop(&mut self) {
+        // SAFETY: `self.0` is initialized and not used again.
+        unsafe { wolfssl_sys::wc_curve25519_free(&mut self.0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(hex: &str) -> [u8; KEY_LEN] {
+        hex::decode(hex).unwrap().try_into().unwrap()
+    }
+
+    /// RFC 7748 section 6.1.
+    #[test]
+    fn rfc7748_diffie_hellman() {
+        let alice = X25519PrivateKey::from_bytes(key(
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        ));
+        let bob = X25519PrivateKey::from_bytes(key(
+            "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+        ));
+        let alice_public = alice.public_key().unwrap();
+        let bob_public = bob.public_key().unwrap();
+        let shared = key("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+
+        assert_eq!(
+            alice_public.as_bytes(),
+            &key("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")
+        );
+        assert_eq!(
+            bob_public.as_bytes(),
+            &key("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+        );
+        assert_eq!(
+            alice.diffie_hellman(&bob_public).unwrap().as_bytes(),
+            &shared
+        );
+        assert_eq!(
+            bob.diffie_hellman(&alice_public).unwrap().as_bytes(),
+            &shared
+        );
+    }
+
+    #[test]
+    fn from_bytes_clamps() {
+        let ones = X25519PrivateKey::from_bytes([0xff; KEY_LEN]).to_bytes();
+        assert_eq!(ones[0], 0xf8);
+        assert_eq!(ones[KEY_LEN - 1], 0x7f);
+
+        let zeros = X25519PrivateKey::from_bytes([0; KEY_LEN]).to_bytes();
+        assert_eq!(zeros[0], 0x00);
+        assert_eq!(zeros[KEY_LEN - 1], 0x40);
+    }
+
+    #[test]
+    fn generated_keys_agree() {
+        let mut rng = WolfRng::new().unwrap();
+        let alice = X25519PrivateKey::generate(&mut rng).unwrap();
+        let bob = X25519PrivateKey::generate(&mut rng).unwrap();
+
+        let ab = alice.diffie_hellman(&bob.public_key().unwrap()).unwrap();
+        let ba = bob.diffie_hellman(&alice.public_key().unwrap()).unwrap();
+        assert_eq!(ab.as_bytes(), ba.as_bytes());
+    }
+
+    #[test]
+    fn rejects_low_order_public_key() {
+        let mut rng = WolfRng::new().unwrap();
+        let key = X25519PrivateKey::generate(&mut rng).unwrap();
+        let zero = X25519PublicKey::from_bytes([0; KEY_LEN]);
+
+        assert!(key.diffie_hellman(&zero).is_err());
+    }
+}
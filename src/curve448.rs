@@ -0,0 +1,272 @@
+//! X448 key agreement (RFC 7748).
+//!
+//! Requires the `curve448` feature and a wolfSSL build with
+//! `HAVE_CURVE448`. Private keys and shared secrets are zeroized when
+//! dropped.
+
+use std::mem::MaybeUninit;
+use std::os::raw::c_int;
+
+use zeroize::Zeroizing;
+
+use crate::ct::constant_time_eq;
+use crate::error::WolfCryptError;
+use crate::rng::WolfRng;
+
+/// The length in bytes of X448 keys and shared secrets.
+pub const KEY_LEN: usize = 56;
+
+/// An X448 private key.
+#[derive(Clone)]
+pub struct X448PrivateKey(Zeroizing<[u8; KEY_LEN]>);
+
+impl X448PrivateKey {
+    /// Generates a new private key.
+    pub fn generate(rng: &mut WolfRng) -> Result<Self, WolfCryptError> {
+        let mut key = Curve448Key::new()?;
+
+        // SAFETY: `rng` is an initialized RNG and `key.0` is initialized.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve448_make_key(rng.as_mut_ptr(), KEY_LEN as c_int, &mut key.0)
+        })?;
+
+        let mut private = Zeroizing::new([0u8; KEY_LEN]);
+        let mut private_len = KEY_LEN as u32;
+
+        // SAFETY: `key.0` holds a private key, and `private` is writable for
+        // `private_len` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve448_export_private_raw_ex(
+                &mut key.0,
+                private.as_mut_ptr(),
+                &mut private_len,
+                wolfssl_sys::EC448_LITTLE_ENDIAN as c_int,
+            )
+        })?;
+
+        Ok(Self(private))
+    }
+
+    /// Wraps an existing private key, in the RFC 7748 (little-endian)
+    /// encoding.
+    ///
+    /// The key is clamped as RFC 7748 section 5 describes, which wolfCrypt
+    /// requires and which does not change the keys it agrees on, so
+    /// [`Self::to_bytes`] may not return `bytes` unchanged.
+    pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Self {
+        let mut key = Zeroizing::new(bytes);
+        key[0] &= 252;
+        key[KEY_LEN - 1] |= 128;
+        Self(key)
+    }
+
+    /// Returns the private key in the RFC 7748 (little-endian) encoding.
+    pub fn to_bytes(&self) -> Zeroizing<[u8; KEY_LEN]> {
+        self.0.clone()
+    }
+
+    /// Computes the matching public key.
+    pub fn public_key(&self) -> Result<X448PublicKey, WolfCryptError> {
+        let mut public = [0u8; KEY_LEN];
+
+        // SAFETY: Both buffers are valid for `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve448_make_pub(
+                KEY_LEN as c_int,
+                public.as_mut_ptr(),
+                KEY_LEN as c_int,
+                self.0.as_ptr(),
+            )
+        })?;
+
+        Ok(X448PublicKey(public))
+    }
+
+    /// Computes the shared secret with `peer`.
+    ///
+    /// Fails if the result is the all-zero value, which indicates a
+    /// low-order peer public key.
+    pub fn diffie_hellman(&self, peer: &X448PublicKey) -> Result<SharedSecret, WolfCryptError> {
+        let mut private = Curve448Key::new()?;
+        let mut public = Curve448Key::new()?;
+
+        // SAFETY: `private.0` is initialized and `self.0` is valid for
+        // `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve448_import_private_ex(
+                self.0.as_ptr(),
+                KEY_LEN as u32,
+                &mut private.0,
+                wolfssl_sys::EC448_LITTLE_ENDIAN as c_int,
+            )
+        })?;
+
+        // SAFETY: `public.0` is initialized and `peer.0` is valid for
+        // `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve448_import_public_ex(
+                peer.0.as_ptr(),
+                KEY_LEN as u32,
+                &mut public.0,
+                wolfssl_sys::EC448_LITTLE_ENDIAN as c_int,
+            )
+        })?;
+
+        let mut secret = Zeroizing::new([0u8; KEY_LEN]);
+        let mut secret_len = KEY_LEN as u32;
+
+        // SAFETY: Both keys were imported above, and `secret` is writable for
+        // `secret_len` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_curve448_shared_secret_ex(
+                &mut private.0,
+                &mut public.0,
+                secret.as_mut_ptr(),
+                &mut secret_len,
+                wolfssl_sys::EC448_LITTLE_ENDIAN as c_int,
+            )
+        })?;
+
+        // Not every wolfSSL build checks this itself.
+        if constant_time_eq(&*secret, &[0u8; KEY_LEN]) {
+            return Err(WolfCryptError::ECC_OUT_OF_RANGE_E);
+        }
+
+        Ok(SharedSecret(secret))
+    }
+}
+
+impl std::fmt::Debug for X448PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("X448PrivateKey").finish_non_exhaustive()
+    }
+}
+
+/// An X448 public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct X448PublicKey([u8; KEY_LEN]);
+
+impl X448PublicKey {
+    /// Wraps a public key in the RFC 7748 (little-endian) encoding.
+    pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the public key in the RFC 7748 (little-endian) encoding.
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+}
+
+/// The output of an X448 key agreement.
+///
+/// This should be passed through a KDF (e.g. [`crate::hkdf`]) rather than
+/// used as a key directly.
+pub struct SharedSecret(Zeroizing<[u8; KEY_LEN]>);
+
+impl SharedSecret {
+    /// Returns the raw shared secret.
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for SharedSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedSecret").finish_non_exhaustive()
+    }
+}
+
+/// A temporary wolfCrypt key object; freeing it scrubs the key material.
+struct Curve448Key(wolfssl_sys::curve448_key);
+
+impl Curve448Key {
+    fn new() -> Result<Self, WolfCryptError> {
+        let mut key = MaybeUninit::<wolfssl_sys::curve448_key>::uninit();
+
+        // SAFETY: `key` is writable for a `curve448_key`.
+        WolfCryptError::check(unsafe { wolfssl_sys::wc_curve448_init(key.as_mut_ptr()) })?;
+
+        // SAFETY: `wc_curve448_init` succeeded.
+        Ok(Self(unsafe { key.assume_init() }))
+    }
+}
+
+impl Drop for Curve448Key {
+    fn drop(&mut self) {
+        // SAFETY: `self.0` is initialized and not used again.
+        unsafe { wolfssl_sys::wc_curve448_free(&mut self.0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(hex: &str) -> [u8; KEY_LEN] {
+        hex::decode(hex).unwrap().try_into().unwrap()
+    }
+
+    // RFC 7748 section 6.2.
+    const ALICE: &str = "9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28d\
+                         d9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b";
+    const ALICE_PUBLIC: &str = "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c\
+                                22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0";
+    const BOB: &str = "1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d\
+                       6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d";
+    const BOB_PUBLIC: &str = "3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b430\
+                              27d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609";
+    const SHARED: &str = "07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282b\
+                          b60c0b56fd2464c335543936521c24403085d59a449a5037514a879d";
+
+    #[test]
+    fn rfc7748_diffie_hellman() {
+        let alice = X448PrivateKey::from_bytes(key(ALICE));
+        let bob = X448PrivateKey::from_bytes(key(BOB));
+        let alice_public = alice.public_key().unwrap();
+        let bob_public = bob.public_key().unwrap();
+        let shared = key(SHARED);
+
+        assert_eq!(alice_public.as_bytes(), &key(ALICE_PUBLIC));
+        assert_eq!(bob_public.as_bytes(), &key(BOB_PUBLIC));
+        assert_eq!(
+            alice.diffie_hellman(&bob_public).unwrap().as_bytes(),
+            &shared
+        );
+        assert_eq!(
+            bob.diffie_hellman(&alice_public).unwrap().as_bytes(),
+            &shared
+        );
+    }
+
+    #[test]
+    fn from_bytes_clamps() {
+        let ones = X448PrivateKey::from_bytes([0xff; KEY_LEN]).to_bytes();
+        assert_eq!(ones[0], 0xfc);
+        assert_eq!(ones[KEY_LEN - 1], 0xff);
+
+        let zeros = X448PrivateKey::from_bytes([0; KEY_LEN]).to_bytes();
+        assert_eq!(zeros[0], 0x00);
+        assert_eq!(zeros[KEY_LEN - 1], 0x80);
+    }
+
+    #[test]
+    fn generated_keys_agree() {
+        let mut rng = WolfRng::new().unwrap();
+        let alice = X448PrivateKey::generate(&mut rng).unwrap();
+        let bob = X448PrivateKey::generate(&mut rng).unwrap();
+
+        let ab = alice.diffie_hellman(&bob.public_key().unwrap()).unwrap();
+        let ba = bob.diffie_hellman(&alice.public_key().unwrap()).unwrap();
+        assert_eq!(ab.as_bytes(), ba.as_bytes());
+    }
+
+    #[test]
+    fn rejects_low_order_public_key() {
+        let mut rng = WolfRng::new().unwrap();
+        let key = X448PrivateKey::generate(&mut rng).unwrap();
+        let zero = X448PublicKey::from_bytes([0; KEY_LEN]);
+
+        assert!(key.diffie_hellman(&zero).is_err());
+    }
+}
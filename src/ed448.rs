@@ -0,0 +1,357 @@
+//! Ed448 signatures (RFC 8032).
+//!
+//! Requires the `curve448` feature and a wolfSSL build with `HAVE_ED448`.
+//! Private keys are zeroized when dropped.
+
+use std::mem::MaybeUninit;
+use std::os::raw::c_int;
+
+use zeroize::Zeroizing;
+
+use crate::error::WolfCryptError;
+use crate::rng::WolfRng;
+
+/// The length in bytes of Ed448 private and public keys.
+pub const KEY_LEN: usize = 57;
+
+/// The length in bytes of an Ed448 signature.
+pub const SIGNATURE_LEN: usize = 114;
+
+/// An Ed448 private key.
+#[derive(Clone)]
+pub struct Ed448PrivateKey(Zeroizing<[u8; KEY_LEN]>);
+
+impl Ed448PrivateKey {
+    /// Generates a new private key.
+    pub fn generate(rng: &mut WolfRng) -> Result<Self, WolfCryptError> {
+        let mut key = Ed448Key::new()?;
+
+        // SAFETY: `rng` is an initialized RNG and `key.0` is initialized.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_ed448_make_key(rng.as_mut_ptr(), KEY_LEN as c_int, &mut key.0)
+        })?;
+
+        let mut private = Zeroizing::new([0u8; KEY_LEN]);
+        let mut private_len = KEY_LEN as u32;
+
+        // SAFETY: `key.0` holds a private key, and `private` is writable for
+        // `private_len` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_ed448_export_private_only(
+                &mut key.0,
+                private.as_mut_ptr(),
+                &mut private_len,
+            )
+        })?;
+
+        Ok(Self(private))
+    }
+
+    /// Wraps an existing private key (the RFC 8032 seed).
+    pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Self {
+        Self(Zeroizing::new(bytes))
+    }
+
+    /// Returns the private key (the RFC 8032 seed).
+    pub fn to_bytes(&self) -> Zeroizing<[u8; KEY_LEN]> {
+        self.0.clone()
+    }
+
+    /// Computes the matching public key.
+    pub fn public_key(&self) -> Result<Ed448PublicKey, WolfCryptError> {
+        let mut key = Ed448Key::new()?;
+        Ok(Ed448PublicKey(self.import(&mut key)?))
+    }
+
+    /// Signs `msg` (pure Ed448) under the domain separation `context`, which
+    /// may be empty but no longer than 255 bytes.
+    pub fn sign(&self, msg: &[u8], context: &[u8]) -> Result<Ed448Signature, WolfCryptError> {
+        let context_len = u8::try_from(context.len()).map_err(|_| WolfCryptError::BAD_FUNC_ARG)?;
+
+        let mut key = Ed448Key::new()?;
+        self.import(&mut key)?;
+
+        let mut sig = [0u8; SIGNATURE_LEN];
+        let mut sig_len = SIGNATURE_LEN as u32;
+
+        // SAFETY: `key.0` holds the key pair, the inputs are valid for their
+        // lengths, and `sig` is writable for `sig_len` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_ed448_sign_msg(
+                msg.as_ptr(),
+                msg.len() as u32,
+                sig.as_mut_ptr(),
+                &mut sig_len,
+                &mut key.0,
+                context.as_ptr(),
+                context_len,
+            )
+        })?;
+
+        Ok(Ed448Signature(sig))
+    }
+
+    /// Loads the full key pair into `key`, returning the public half.
+    fn import(&self, key: &mut Ed448Key) -> Result<[u8; KEY_LEN], WolfCryptError> {
+        // SAFETY: `key.0` is initialized and `self.0` is valid for `KEY_LEN`
+        // bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_ed448_import_private_only(self.0.as_ptr(), KEY_LEN as u32, &mut key.0)
+        })?;
+
+        let mut public = [0u8; KEY_LEN];
+
+        // SAFETY: `key.0` holds the private key and `public` is writable for
+        // `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_ed448_make_public(&mut key.0, public.as_mut_ptr(), KEY_LEN as u32)
+        })?;
+
+        // SAFETY: `key.0` is initialized and both halves are valid for
+        // `KEY_LEN` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_ed448_import_private_key(
+                self.0.as_ptr(),
+                KEY_LEN as u32,
+                public.as_ptr(),
+                KEY_LEN as u32,
+                &mut key.0,
+            )
+        })?;
+
+        Ok(public)
+    }
+}
+
+impl std::fmt::Debug for Ed448PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ed448PrivateKey").finish_non_exhaustive()
+    }
+}
+
+/// Signs with an empty context.
+#[cfg(feature = "signature")]
+impl signature::Signer<Ed448Signature> for Ed448PrivateKey {
+    fn try_sign(&self, msg: &[u8]) -> Result<Ed448Signature, signature::Error> {
+        self.sign(msg, &[]).map_err(signature::Error::from_source)
+    }
+}
+
+/// An Ed448 public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ed448PublicKey([u8; KEY_LEN]);
+
+impl Ed448PublicKey {
+    /// Wraps an encoded public key.
+    pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the encoded public key.
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        &self.0
+    }
+
+    /// Returns whether `sig` is a valid signature of `msg` under `context`.
+    pub fn verify(&self, msg: &[u8], context: &[u8], sig: &Ed448Signature) -> bool {
+        let Ok(context_len) = u8::try_from(context.len()) else {
+            return false;
+        };
+        let Ok(mut key) = Ed448Key::new() else {
+            return false;
+        };
+
+        // SAFETY: `key.0` is initialized and `self.0` is valid for `KEY_LEN`
+        // bytes.
+        let ret = unsafe {
+            wolfssl_sys::wc_ed448_import_public(self.0.as_ptr(), KEY_LEN as u32, &mut key.0)
+        };
+        if ret != 0 {
+            return false;
+        }
+
+        let mut res: c_int = 0;
+
+        // SAFETY: `key.0` holds the public key and the inputs are valid for
+        // their lengths.
+        let ret = unsafe {
+            wolfssl_sys::wc_ed448_verify_msg(
+                sig.0.as_ptr(),
+                SIGNATURE_LEN as u32,
+                msg.as_ptr(),
+                msg.len() as u32,
+                &mut res,
+                &mut key.0,
+                context.as_ptr(),
+                context_len,
+            )
+        };
+
+        ret == 0 && res == 1
+    }
+}
+
+/// Verifies with an empty context.
+#[cfg(feature = "signature")]
+impl signature::Verifier<Ed448Signature> for Ed448PublicKey {
+    fn verify(&self, msg: &[u8], signature: &Ed448Signature) -> Result<(), signature::Error> {
+        if Ed448PublicKey::verify(self, msg, &[], signature) {
+            Ok(())
+        } else {
+            Err(signature::Error::new())
+        }
+    }
+}
+
+/// An Ed448 signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ed448Signature([u8; SIGNATURE_LEN]);
+
+impl Ed448Signature {
+    /// Wraps an encoded signature.
+    pub fn from_bytes(bytes: [u8; SIGNATURE_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the encoded signature.
+    pub fn as_bytes(&self) -> &[u8; SIGNATURE_LEN] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "signature")]
+impl TryFrom<&[u8]> for Ed448Signature {
+    type Error = signature::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| signature::Error::new())
+    }
+}
+
+#[cfg(feature = "signature")]
+impl From<Ed448Signature> for [u8; SIGNATURE_LEN] {
+    fn from(sig: Ed448Signature) -> Self {
+        sig.0
+    }
+}
+
+#[cfg(feature = "signature")]
+impl signature::SignatureEncoding for Ed448Signature {
+    type Repr = [u8; SIGNATURE_LEN];
+}
+
+/// A temporary wolfCrypt key object; freeing it scrubs the key material.
+struct Ed448Key(wolfssl_sys::ed448_key);
+
+impl Ed448Key {
+    fn new() -> Result<Self, WolfCryptError> {
+        let mut key = MaybeUninit::<wolfssl_sys::ed448_key>::uninit();
+
+        // SAFETY: `key` is writable for an `ed448_key`.
+        WolfCryptError::check(unsafe { wolfssl_sys::wc_ed448_init(key.as_mut_ptr()) })?;
+
+        // SAFETY: `wc_ed448_init` succeeded.
+        Ok(Self(unsafe { key.assume_init() }))
+    }
+}
+
+impl Drop for Ed448Key {
+    fn drop(&mut self) {
+        // SAFETY: `self.0` is initialized and not used again.
+        unsafe { wolfssl_sys::wc_ed448_free(&mut self.0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn array<const N: usize>(s: &str) -> [u8; N] {
+        hex::decode(s).unwrap().try_into().unwrap()
+    }
+
+    /// RFC 8032 §7.4, "-----Blank" and "-----1 octet".
+    #[test]
+    fn rfc8032_known_answers() {
+        let vectors: [(&str, &str, &[u8], &str); 2] = [
+            (
+                "6c82a562cb808d10d632be89c8513ebf6c929f34ddfa8c9f63c9960ef6e348a3\
+                 528c8a3fcc2f044e39a3fc5b94492f8f032e7549a20098f95b",
+                "5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a0ea75d80e96778\
+                 edf124769b46c7061bd6783df1e50f6cd1fa1abeafe8256180",
+                b"",
+                "533a37f6bbe457251f023c0d88f976ae2dfb504a843e34d2074fd823d41a591f\
+                 2b233f034f628281f2fd7a22ddd47d7828c59bd0a21bfd3980ff0d2028d4b18a\
+                 9df63e006c5d1c2d345b925d8dc00b4104852db99ac5c7cdda8530a113a0f4db\
+                 b61149f05a7363268c71d95808ff2e652600",
+            ),
+            (
+                "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463a\
+                 fbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+                "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c086\
+                 6aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+                b"\x03",
+                "26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb0bd0087810f435\
+                 2541b143c4b981b7e18f62de8ccdf633fc1bf037ab7cd779805e0dbcc0aae1cb\
+                 cee1afb2e027df36bc04dcecbf154336c19f0af7e0a6472905e799f1953d2a0f\
+                 f3348ab21aa4adafd1d234441cf807c03a00",
+            ),
+        ];
+
+        for (secret, public, msg, sig) in vectors {
+            let key = Ed448PrivateKey::from_bytes(array(secret));
+            let public = Ed448PublicKey::from_bytes(array(public));
+            let sig = Ed448Signature::from_bytes(array(sig));
+
+            assert_eq!(key.public_key().unwrap(), public);
+            assert_eq!(key.sign(msg, &[]).unwrap(), sig);
+            assert!(public.verify(msg, &[], &sig));
+        }
+    }
+
+    #[test]
+    fn verify_rejects_tampering() {
+        let mut rng = WolfRng::new().unwrap();
+        let key = Ed448PrivateKey::generate(&mut rng).unwrap();
+        let public = key.public_key().unwrap();
+        let sig = key.sign(b"message", b"context").unwrap();
+
+        assert!(public.verify(b"message", b"context", &sig));
+        assert!(!public.verify(b"messagf", b"context", &sig));
+        assert!(!public.verify(b"message", b"", &sig));
+        assert!(!public.verify(b"message", &[0; 256], &sig));
+
+        let mut bytes = *sig.as_bytes();
+        bytes[0] ^= 1;
+        assert!(!public.verify(b"message", b"context", &Ed448Signature::from_bytes(bytes)));
+
+        let other = Ed448PrivateKey::generate(&mut rng).unwrap();
+        assert!(!other
+            .public_key()
+            .unwrap()
+            .verify(b"message", b"context", &sig));
+    }
+
+    #[cfg(feature = "signature")]
+    #[test]
+    fn signature_traits() {
+        use signature::{SignatureEncoding, Signer, Verifier};
+
+        let mut rng = WolfRng::new().unwrap();
+        let key = Ed448PrivateKey::generate(&mut rng).unwrap();
+        let public = key.public_key().unwrap();
+
+        let sig: Ed448Signature = key.try_sign(b"message").unwrap();
+        assert_eq!(sig, key.sign(b"message", &[]).unwrap());
+        assert!(Verifier::verify(&public, b"message", &sig).is_ok());
+        assert!(Verifier::verify(&public, b"messagf", &sig).is_err());
+
+        let bytes = sig.to_bytes();
+        assert_eq!(Ed448Signature::try_from(&bytes[..]).unwrap(), sig);
+        assert!(Ed448Signature::try_from(&bytes[1..]).is_err());
+        assert!(Ed448Signature::try_from(&[0u8; SIGNATURE_LEN + 1][..]).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! Errors shared by the wolfCrypt wrappers.
+
+use std::ffi::CStr;
+use std::os::raw::c_int;
+
+/// An error code returned by a wolfCrypt function.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WolfCryptError(c_int);
+
+impl WolfCryptError {
+    /// `BAD_FUNC_ARG`, for arguments rejected before reaching wolfCrypt.
+    pub(crate) const BAD_FUNC_ARG: Self = Self(wolfssl_sys::wolfCrypt_ErrorCodes_BAD_FUNC_ARG);
+
+    /// `ECC_OUT_OF_RANGE_E`, for key agreement results rejected as weak.
+    pub(crate) const ECC_OUT_OF_RANGE_E: Self =
+        Self(wolfssl_sys::wolfCrypt_ErrorCodes_ECC_OUT_OF_RANGE_E);
+
+    /// Returns the raw (negative) wolfCrypt error code.
+    pub fn code(&self) -> i32 {
+        self.0
+    }
+
+    /// Maps a wolfCrypt return value to a `Result`, treating negative values
+    /// as errors.
+    pub(crate) fn check(ret: c_int) -> Result<c_int, Self> {
+        if ret < 0 {
+            Err(Self(ret))
+        } else {
+            Ok(ret)
+        }
+    }
+}
+
+impl std::fmt::Debug for WolfCryptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WolfCryptError({}: {self})", self.0)
+    }
+}
+
+impl std::fmt::Display for WolfCryptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // SAFETY: wolfCrypt returns a pointer to a static, NUL terminated
+        // string for any input.
+        let msg = unsafe { CStr::from_ptr(wolfssl_sys::wc_GetErrorString(self.0)) };
+        f.write_str(&msg.to_string_lossy())
+    }
+}
+
+impl std::error::Error for WolfCryptError {}
@@ -0,0 +1,110 @@
+//! FIPS 140 module status and self-tests.
+//!
+//! [`is_enabled`] is always available. Everything else requires the `fips`
+//! feature and a FIPS build of wolfssl-sys.
+
+#[cfg(feature = "fips")]
+use std::ffi::CStr;
+#[cfg(feature = "fips")]
+use std::os::raw::{c_char, c_int};
+#[cfg(feature = "fips")]
+use std::sync::RwLock;
+
+#[cfg(feature = "fips")]
+use crate::error::WolfCryptError;
+
+/// Errors reported by the FIPS module.
+#[cfg(feature = "fips")]
+#[derive(Debug, thiserror::Error)]
+pub enum FipsError {
+    /// One or more conditional algorithm self-tests (CASTs) failed.
+    #[error("{0} FIPS self-tests failed")]
+    SelfTestsFailed(i32),
+    /// The module is in an error state and refuses all cryptographic
+    /// operations.
+    #[error("FIPS module in error state: {0}")]
+    Module(WolfCryptError),
+}
+
+/// Returns whether the linked wolfSSL is a FIPS build.
+pub fn is_enabled() -> bool {
+    // SAFETY: No preconditions.
+    unsafe { wolfssl_sys::wolfSSL_FIPS_mode() == 1 }
+}
+
+/// Returns an error if the module failed its power-on integrity check or a
+/// self-test, and is therefore refusing cryptographic operations.
+#[cfg(feature = "fips")]
+pub fn status() -> Result<(), FipsError> {
+    // SAFETY: No preconditions.
+    match unsafe { wolfssl_sys::wolfCrypt_GetStatus_fips() } {
+        0 => Ok(()),
+        err => Err(FipsError::Module(module_error(err))),
+    }
+}
+
+/// Runs every conditional algorithm self-test (CAST) now, rather than on
+/// first use of each algorithm.
+///
+/// Deployments that must demonstrate a healthy module before serving
+/// traffic should call this at startup.
+#[cfg(feature = "fips")]
+pub fn run_self_tests() -> Result<(), FipsError> {
+    // SAFETY: No preconditions.
+    match unsafe { wolfssl_sys::wc_RunAllCast_fips() } {
+        0 => status(),
+        failed => Err(FipsError::SelfTestsFailed(failed)),
+    }
+}
+
+#[cfg(feature = "fips")]
+type ErrorCallback = Box<dyn Fn(&FipsError, Option<&str>) + Send + Sync>;
+
+#[cfg(feature = "fips")]
+static ERROR_CALLBACK: RwLock<Option<ErrorCallback>> = RwLock::new(None);
+
+/// Registers `callback` to be invoked whenever the module reports a failure,
+/// replacing any previous callback.
+///
+/// The callback receives the error and, for integrity check failures, the
+/// expected in-core hash. It runs on whichever thread hit the failure, and
+/// a panic inside it aborts the process.
+#[cfg(feature = "fips")]
+pub fn set_error_callback(callback: impl Fn(&FipsError, Option<&str>) + Send + Sync + 'static) {
+    *ERROR_CALLBACK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Box::new(callback));
+
+    // SAFETY: `fips_callback` has the signature wolfCrypt expects and, being
+    // a plain function, lives for the remainder of the program.
+    unsafe { wolfssl_sys::wolfCrypt_SetCb_fips(Some(fips_callback)) };
+}
+
+#[cfg(feature = "fips")]
+fn module_error(err: c_int) -> WolfCryptError {
+    // wolfCrypt reports FIPS failures as negative error codes.
+    WolfCryptError::check(-err.abs())
+        .err()
+        .unwrap_or(WolfCryptError::BAD_FUNC_ARG)
+}
+
+#[cfg(feature = "fips")]
+unsafe extern "C" fn fips_callback(ok: c_int, err: c_int, hash: *const c_char) {
+    if ok != 0 {
+        return;
+    }
+
+    let hash = (!hash.is_null()).then(|| {
+        // SAFETY: wolfCrypt hands us a NUL terminated string which stays
+        // valid for the duration of this call.
+        unsafe { CStr::from_ptr(hash) }.to_string_lossy()
+    });
+
+    let error = FipsError::Module(module_error(err));
+    let callback = ERROR_CALLBACK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(callback) = callback.as_ref() {
+        callback(&error, hash.as_deref());
+    }
+}
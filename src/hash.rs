@@ -0,0 +1,386 @@
+//! SHA-2 message digests.
+//!
+//! Each hasher supports streaming use via `update`/`finalize`, and a one-shot
+//! `digest`. With the `digest` feature enabled they also implement the
+//! [`digest`] crate's traits, and therefore `digest::Digest`.
+//!
+//! wolfCrypt only fails to hash when it is misconfigured or, in FIPS builds,
+//! when the module is in an error state. The plain methods panic in that
+//! case; the `try_*` variants report it instead.
+
+use std::mem::MaybeUninit;
+use std::os::raw::c_int;
+
+use crate::error::WolfCryptError;
+
+/// A SHA-2 variant, for APIs that are generic over the hash function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    /// SHA-256
+    Sha256,
+    /// SHA-384
+    Sha384,
+    /// SHA-512
+    Sha512,
+}
+
+impl HashAlgorithm {
+    /// Returns the digest length in bytes.
+    pub fn output_len(self) -> usize {
+        match self {
+            Self::Sha256 => Sha256::OUTPUT_LEN,
+            Self::Sha384 => Sha384::OUTPUT_LEN,
+            Self::Sha512 => Sha512::OUTPUT_LEN,
+        }
+    }
+
+    /// Returns the digest of `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if wolfCrypt fails to hash; see [`Self::try_digest`].
+    pub fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self.try_digest(data) {
+            Ok(digest) => digest,
+            Err(e) => panic!("{self:?} failed: {e}"),
+        }
+    }
+
+    /// Returns the digest of `data`, or the error wolfCrypt reported.
+    pub fn try_digest(self, data: &[u8]) -> Result<Vec<u8>, WolfCryptError> {
+        Ok(match self {
+            Self::Sha256 => Sha256::try_digest(data)?.to_vec(),
+            Self::Sha384 => Sha384::try_digest(data)?.to_vec(),
+            Self::Sha512 => Sha512::try_digest(data)?.to_vec(),
+        })
+    }
+
+    /// Returns the matching `wc_HashType` value.
+    pub(crate) fn wc_type(self) -> c_int {
+        match self {
+            Self::Sha256 => wolfssl_sys::wc_HashType_WC_HASH_TYPE_SHA256 as c_int,
+            Self::Sha384 => wolfssl_sys::wc_HashType_WC_HASH_TYPE_SHA384 as c_int,
+            Self::Sha512 => wolfssl_sys::wc_HashType_WC_HASH_TYPE_SHA512 as c_int,
+        }
+    }
+}
+
+macro_rules! sha2 {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $oneshot:ident, $ctx:ty, $len:literal, $size:ty,
+        $init:ident, $update:ident, $final:ident, $free:ident, $copy:ident
+    ) => {
+        $(#[$meta])*
+        pub struct $name($ctx);
+
+        impl $name {
+            /// The digest length in bytes.
+            pub const OUTPUT_LEN: usize = $len;
+
+            /// Creates a hasher with empty input.
+            ///
+            /// # Panics
+            ///
+            /// Panics if wolfCrypt fails; see [`Self::try_new`].
+            pub fn new() -> Self {
+                Self::try_new().expect(concat!(stringify!($init), " failed"))
+            }
+
+            /// Creates a hasher with empty input, or returns the error
+            /// wolfCrypt reported.
+            pub fn try_new() -> Result<Self, WolfCryptError> {
+                let mut ctx = MaybeUninit::<$ctx>::uninit();
+
+                // SAFETY: `ctx` is writable for the context type.
+                WolfCryptError::check(unsafe { wolfssl_sys::$init(ctx.as_mut_ptr()) })?;
+
+                // SAFETY: Initialization succeeded.
+                Ok(Self(unsafe { ctx.assume_init() }))
+            }
+
+            /// Feeds `data` into the hasher.
+            ///
+            /// # Panics
+            ///
+            /// Panics if wolfCrypt fails; see [`Self::try_update`].
+            pub fn update(&mut self, data: &[u8]) {
+                self.try_update(data).expect(concat!(stringify!($update), " failed"))
+            }
+
+            /// Feeds `data` into the hasher, or returns the error wolfCrypt
+            /// reported.
+            pub fn try_update(&mut self, data: &[u8]) -> Result<(), WolfCryptError> {
+                for chunk in data.chunks(u32::MAX as usize) {
+                    // SAFETY: `self.0` is initialized and `chunk` is valid for
+                    // its length.
+                    WolfCryptError::check(unsafe {
+                        wolfssl_sys::$update(&mut self.0, chunk.as_ptr(), chunk.len() as u32)
+                    })?;
+                }
+                Ok(())
+            }
+
+            /// Consumes the hasher and returns the digest.
+            ///
+            /// # Panics
+            ///
+            /// Panics if wolfCrypt fails; see [`Self::try_finalize`].
+            pub fn finalize(mut self) -> [u8; $len] {
+                self.finalize_reset()
+            }
+
+            /// Consumes the hasher and returns the digest, or the error
+            /// wolfCrypt reported.
+            pub fn try_finalize(mut self) -> Result<[u8; $len], WolfCryptError> {
+                self.try_finalize_reset()
+            }
+
+            /// Returns the digest of `data`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if wolfCrypt fails; see [`Self::try_digest`].
+            pub fn digest(data: &[u8]) -> [u8; $len] {
+                let mut hasher = Self::new();
+                hasher.update(data);
+                hasher.finalize()
+            }
+
+            /// Returns the digest of `data`, or the error wolfCrypt reported.
+            pub fn try_digest(data: &[u8]) -> Result<[u8; $len], WolfCryptError> {
+                let mut hasher = Self::try_new()?;
+                hasher.try_update(data)?;
+                hasher.try_finalize()
+            }
+
+            /// Returns a copy of the hasher's state, or the error wolfCrypt
+            /// reported. [`Clone`] panics instead.
+            pub fn try_clone(&self) -> Result<Self, WolfCryptError> {
+                let mut ctx = MaybeUninit::<$ctx>::uninit();
+
+                // SAFETY: `self.0` is initialized, and wolfCrypt only reads
+                // from it despite the mutable pointer. `ctx` is writable.
+                WolfCryptError::check(unsafe {
+                    wolfssl_sys::$copy(std::ptr::addr_of!(self.0).cast_mut(), ctx.as_mut_ptr())
+                })?;
+
+                // SAFETY: The copy succeeded.
+                Ok(Self(unsafe { ctx.assume_init() }))
+            }
+
+            fn finalize_reset(&mut self) -> [u8; $len] {
+                self.try_finalize_reset().expect(concat!(stringify!($final), " failed"))
+            }
+
+            // wolfCrypt's final functions reinitialize the context, so the
+            // hasher is ready for reuse afterwards.
+            fn try_finalize_reset(&mut self) -> Result<[u8; $len], WolfCryptError> {
+                let mut out = [0u8; $len];
+
+                // SAFETY: `self.0` is initialized and `out` has room for the
+                // digest.
+                WolfCryptError::check(unsafe { wolfssl_sys::$final(&mut self.0, out.as_mut_ptr()) })?;
+
+                Ok(out)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl Clone for $name {
+            fn clone(&self) -> Self {
+                self.try_clone().expect(concat!(stringify!($copy), " failed"))
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                // SAFETY: `self.0` is initialized and not used again.
+                unsafe { wolfssl_sys::$free(&mut self.0) }
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($name)).finish_non_exhaustive()
+            }
+        }
+
+        #[doc = concat!("Returns the ", stringify!($name), " digest of `data`.")]
+        ///
+        /// # Panics
+        ///
+        #[doc = concat!("Panics if wolfCrypt fails; see [`", stringify!($name), "::try_digest`].")]
+        pub fn $oneshot(data: &[u8]) -> [u8; $len] {
+            $name::digest(data)
+        }
+
+        #[cfg(feature = "digest")]
+        impl digest::HashMarker for $name {}
+
+        #[cfg(feature = "digest")]
+        impl digest::OutputSizeUser for $name {
+            type OutputSize = $size;
+        }
+
+        #[cfg(feature = "digest")]
+        impl digest::Update for $name {
+            fn update(&mut self, data: &[u8]) {
+                $name::update(self, data)
+            }
+        }
+
+        #[cfg(feature = "digest")]
+        impl digest::FixedOutput for $name {
+            fn finalize_into(mut self, out: &mut digest::Output<Self>) {
+                out.copy_from_slice(&self.finalize_reset())
+            }
+        }
+
+        #[cfg(feature = "digest")]
+        impl digest::Reset for $name {
+            fn reset(&mut self) {
+                *self = Self::new();
+            }
+        }
+
+        #[cfg(feature = "digest")]
+        impl digest::FixedOutputReset for $name {
+            fn finalize_into_reset(&mut self, out: &mut digest::Output<Self>) {
+                out.copy_from_slice(&self.finalize_reset())
+            }
+        }
+    };
+}
+
+sha2!(
+    /// A SHA-256 hasher.
+    Sha256, sha256, wolfssl_sys::wc_Sha256, 32, digest::consts::U32,
+    wc_InitSha256, wc_Sha256Update, wc_Sha256Final, wc_Sha256Free, wc_Sha256Copy
+);
+
+sha2!(
+    /// A SHA-384 hasher.
+    Sha384, sha384, wolfssl_sys::wc_Sha384, 48, digest::consts::U48,
+    wc_InitSha384, wc_Sha384Update, wc_Sha384Final, wc_Sha384Free, wc_Sha384Copy
+);
+
+sha2!(
+    /// A SHA-512 hasher.
+    Sha512, sha512, wolfssl_sys::wc_Sha512, 64, digest::consts::U64,
+    wc_InitSha512, wc_Sha512Update, wc_Sha512Final, wc_Sha512Free, wc_Sha512Copy
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC: &[u8] = b"abc";
+    const TWO_BLOCK_256: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    const TWO_BLOCK_512: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+                                   hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    /// NIST's FIPS 180-4 example messages, plus the empty message.
+    fn vectors() -> [(HashAlgorithm, &'static [u8], &'static str); 9] {
+        [
+            (
+                HashAlgorithm::Sha256,
+                b"",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                HashAlgorithm::Sha256,
+                ABC,
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                HashAlgorithm::Sha256,
+                TWO_BLOCK_256,
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+            (
+                HashAlgorithm::Sha384,
+                b"",
+                "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da\
+                 274edebfe76f65fbd51ad2f14898b95b",
+            ),
+            (
+                HashAlgorithm::Sha384,
+                ABC,
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+                 8086072ba1e7cc2358baeca134c825a7",
+            ),
+            (
+                HashAlgorithm::Sha384,
+                TWO_BLOCK_512,
+                "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712\
+                 fcc7c71a557e2db966c3e9fa91746039",
+            ),
+            (
+                HashAlgorithm::Sha512,
+                b"",
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+                 47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+            ),
+            (
+                HashAlgorithm::Sha512,
+                ABC,
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+                 2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            ),
+            (
+                HashAlgorithm::Sha512,
+                TWO_BLOCK_512,
+                "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+                 501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+            ),
+        ]
+    }
+
+    #[test]
+    fn known_answers() {
+        for (algorithm, msg, expected) in vectors() {
+            let digest = algorithm.digest(msg);
+            assert_eq!(digest.len(), algorithm.output_len());
+            assert_eq!(hex::encode(digest), expected, "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let mut hasher = Sha256::new();
+        for chunk in TWO_BLOCK_256.chunks(7) {
+            hasher.update(chunk);
+        }
+        let copy = hasher.clone();
+
+        assert_eq!(hasher.finalize(), sha256(TWO_BLOCK_256));
+        assert_eq!(copy.finalize(), sha256(TWO_BLOCK_256));
+
+        let mut hasher = Sha512::new();
+        hasher.update(&TWO_BLOCK_512[..50]);
+        hasher.update(&TWO_BLOCK_512[50..]);
+        assert_eq!(hasher.finalize(), sha512(TWO_BLOCK_512));
+    }
+
+    #[test]
+    fn fallible_variants_match() {
+        for (algorithm, msg, expected) in vectors() {
+            assert_eq!(hex::encode(algorithm.try_digest(msg).unwrap()), expected);
+        }
+
+        let mut hasher = Sha384::try_new().unwrap();
+        hasher.try_update(&TWO_BLOCK_512[..9]).unwrap();
+        let mut copy = hasher.try_clone().unwrap();
+        hasher.try_update(&TWO_BLOCK_512[9..]).unwrap();
+        copy.try_update(&TWO_BLOCK_512[9..]).unwrap();
+
+        assert_eq!(hasher.try_finalize().unwrap(), sha384(TWO_BLOCK_512));
+        assert_eq!(copy.try_finalize().unwrap(), sha384(TWO_BLOCK_512));
+    }
+}
@@ -0,0 +1,165 @@
+//! HKDF key derivation (RFC 5869).
+
+use zeroize::Zeroizing;
+
+use crate::error::WolfCryptError;
+use crate::hash::HashAlgorithm;
+
+/// Derives a pseudorandom key from `ikm` and `salt` (HKDF-Extract).
+///
+/// The returned key is [`HashAlgorithm::output_len`] bytes long. An empty
+/// `salt` is treated as a string of zeroes, as per the RFC.
+pub fn extract(
+    algorithm: HashAlgorithm,
+    salt: &[u8],
+    ikm: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, WolfCryptError> {
+    let mut prk = Zeroizing::new(vec![0u8; algorithm.output_len()]);
+
+    // SAFETY: The inputs are valid for their lengths, and `prk` has room for
+    // a digest of `algorithm`.
+    WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_HKDF_Extract(
+            algorithm.wc_type(),
+            ptr_or_null(salt),
+            salt.len() as u32,
+            ikm.as_ptr(),
+            ikm.len() as u32,
+            prk.as_mut_ptr(),
+        )
+    })?;
+
+    Ok(prk)
+}
+
+/// Expands `prk` into `out.len()` bytes of keying material bound to `info`
+/// (HKDF-Expand).
+///
+/// `out` may be at most 255 times [`HashAlgorithm::output_len`] bytes long.
+pub fn expand(
+    algorithm: HashAlgorithm,
+    prk: &[u8],
+    info: &[u8],
+    out: &mut [u8],
+) -> Result<(), WolfCryptError> {
+    // SAFETY: The inputs are valid for their lengths, and `out` is writable
+    // for its length.
+    WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_HKDF_Expand(
+            algorithm.wc_type(),
+            prk.as_ptr(),
+            prk.len() as u32,
+            ptr_or_null(info),
+            info.len() as u32,
+            out.as_mut_ptr(),
+            out.len() as u32,
+        )
+    })?;
+
+    Ok(())
+}
+
+/// Runs HKDF-Extract followed by HKDF-Expand, filling `out`.
+///
+/// The arguments are in the same order as for [`extract`] and [`expand`].
+pub fn hkdf(
+    algorithm: HashAlgorithm,
+    salt: &[u8],
+    ikm: &[u8],
+    info: &[u8],
+    out: &mut [u8],
+) -> Result<(), WolfCryptError> {
+    // SAFETY: The inputs are valid for their lengths, and `out` is writable
+    // for its length.
+    WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_HKDF(
+            algorithm.wc_type(),
+            ikm.as_ptr(),
+            ikm.len() as u32,
+            ptr_or_null(salt),
+            salt.len() as u32,
+            ptr_or_null(info),
+            info.len() as u32,
+            out.as_mut_ptr(),
+            out.len() as u32,
+        )
+    })?;
+
+    Ok(())
+}
+
+// wolfCrypt substitutes the RFC defaults for NULL salt and info, and some
+// builds (e.g. FIPS) reject zero length HMAC keys, so pass NULL rather than a
+// dangling pointer for empty inputs.
+fn ptr_or_null(buf: &[u8]) -> *const u8 {
+    if buf.is_empty() {
+        std::ptr::null()
+    } else {
+        buf.as_ptr()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Vector {
+        ikm: Vec<u8>,
+        salt: Vec<u8>,
+        info: Vec<u8>,
+        prk: &'static str,
+        okm: &'static str,
+    }
+
+    /// RFC 5869 appendix A.1 to A.3, the SHA-256 cases.
+    fn vectors() -> [Vector; 3] {
+        [
+            Vector {
+                ikm: vec![0x0b; 22],
+                salt: (0x00..=0x0c).collect(),
+                info: (0xf0..=0xf9).collect(),
+                prk: "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+                okm: "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+                      34007208d5b887185865",
+            },
+            Vector {
+                ikm: (0x00..=0x4f).collect(),
+                salt: (0x60..=0xaf).collect(),
+                info: (0xb0..=0xff).collect(),
+                prk: "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+                okm: "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+                      59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+                      cc30c58179ec3e87c14c01d5c1f3434f1d87",
+            },
+            Vector {
+                ikm: vec![0x0b; 22],
+                salt: Vec::new(),
+                info: Vec::new(),
+                prk: "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+                okm: "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d\
+                      9d201395faa4b61a96c8",
+            },
+        ]
+    }
+
+    #[test]
+    fn rfc5869_extract_expand() {
+        for v in vectors() {
+            let prk = extract(HashAlgorithm::Sha256, &v.salt, &v.ikm).unwrap();
+            assert_eq!(hex::encode(&*prk), v.prk);
+
+            let mut okm = vec![0u8; v.okm.len() / 2];
+            expand(HashAlgorithm::Sha256, &prk, &v.info, &mut okm).unwrap();
+            assert_eq!(hex::encode(&okm), v.okm);
+        }
+    }
+
+    #[test]
+    fn rfc5869_one_shot() {
+        for v in vectors() {
+            let mut okm = vec![0u8; v.okm.len() / 2];
+            hkdf(HashAlgorithm::Sha256, &v.salt, &v.ikm, &v.info, &mut okm).unwrap();
+            assert_eq!(hex::encode(&okm), v.okm);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! HMAC message authentication.
+
+use crate::ct::constant_time_eq;
+use crate::error::WolfCryptError;
+use crate::hash::HashAlgorithm;
+
+/// An HMAC computation keyed for a particular hash function.
+pub struct Hmac {
+    /// Boxed so that the key is set up in its final location, rather than
+    /// left behind in stack copies when the value moves.
+    ctx: Box<wolfssl_sys::Hmac>,
+    algorithm: HashAlgorithm,
+}
+
+impl Hmac {
+    /// Creates an HMAC instance using `algorithm` and `key`.
+    ///
+    /// In FIPS builds wolfCrypt rejects keys shorter than 112 bits.
+    pub fn new(algorithm: HashAlgorithm, key: &[u8]) -> Result<Self, WolfCryptError> {
+        let mut ctx = Box::<wolfssl_sys::Hmac>::new_uninit();
+
+        // SAFETY: `ctx` is writable for an `Hmac`. A null heap hint and
+        // `INVALID_DEVID` select the default software implementation.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_HmacInit(
+                ctx.as_mut_ptr(),
+                std::ptr::null_mut(),
+                wolfssl_sys::INVALID_DEVID,
+            )
+        })?;
+
+        // SAFETY: `wc_HmacInit` succeeded.
+        let mut hmac = Self {
+            ctx: unsafe { ctx.assume_init() },
+            algorithm,
+        };
+
+        // SAFETY: `hmac.ctx` is initialized and `key` is valid for its
+        // length.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_HmacSetKey(
+                &mut *hmac.ctx,
+                algorithm.wc_type(),
+                key.as_ptr(),
+                key.len() as u32,
+            )
+        })?;
+
+        Ok(hmac)
+    }
+
+    /// Returns the hash function this instance was created with.
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
+    /// Feeds `data` into the MAC.
+    ///
+    /// This only fails if wolfCrypt does, e.g. because a FIPS module is in
+    /// an error state.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), WolfCryptError> {
+        for chunk in data.chunks(u32::MAX as usize) {
+            // SAFETY: `self.ctx` is keyed and `chunk` is valid for its length.
+            WolfCryptError::check(unsafe {
+                wolfssl_sys::wc_HmacUpdate(&mut *self.ctx, chunk.as_ptr(), chunk.len() as u32)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Consumes the instance and returns the tag, which is
+    /// [`HashAlgorithm::output_len`] bytes long.
+    pub fn finalize(mut self) -> Result<Vec<u8>, WolfCryptError> {
+        let mut tag = vec![0u8; self.algorithm.output_len()];
+
+        // SAFETY: `self.ctx` is keyed and `tag` has room for the digest.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_HmacFinal(&mut *self.ctx, tag.as_mut_ptr())
+        })?;
+
+        Ok(tag)
+    }
+
+    /// Consumes the instance and checks, in constant time, whether the tag
+    /// equals `expected`.
+    pub fn verify(self, expected: &[u8]) -> Result<bool, WolfCryptError> {
+        Ok(constant_time_eq(&self.finalize()?, expected))
+    }
+
+    /// Computes the tag of `data` under `key` in one step.
+    pub fn mac(
+        algorithm: HashAlgorithm,
+        key: &[u8],
+        data: &[u8],
+    ) -> Result<Vec<u8>, WolfCryptError> {
+        let mut hmac = Self::new(algorithm, key)?;
+        hmac.update(data)?;
+        hmac.finalize()
+    }
+}
+
+impl Drop for Hmac {
+    fn drop(&mut self) {
+        // SAFETY: `self.ctx` is initialized and not used again. It is plain
+        // old data, so it can be scrubbed of the key once freed.
+        unsafe {
+            wolfssl_sys::wc_HmacFree(&mut *self.ctx);
+            zeroize::zeroize_flat_type(&mut *self.ctx);
+        }
+    }
+}
+
+impl std::fmt::Debug for Hmac {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hmac")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Vector {
+        key: Vec<u8>,
+        data: Vec<u8>,
+        sha256: &'static str,
+        sha384: &'static str,
+        sha512: &'static str,
+    }
+
+    /// RFC 4231 test cases 1, 2, 3 and 6.
+    fn vectors() -> [Vector; 4] {
+        [
+            Vector {
+                key: vec![0x0b; 20],
+                data: b"Hi There".to_vec(),
+                sha256: "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+                sha384: "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59c\
+                         faea9ea9076ede7f4af152e8b2fa9cb6",
+                sha512: "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+                         daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            },
+            Vector {
+                key: b"Jefe".to_vec(),
+                data: b"what do ya want for nothing?".to_vec(),
+                sha256: "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                sha384: "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e\
+                         8e2240ca5e69e2c78b3239ecfab21649",
+                sha512: "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554\
+                         9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            },
+            Vector {
+                key: vec![0xaa; 20],
+                data: vec![0xdd; 50],
+                sha256: "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+                sha384: "88062608d3e6ad8a0aa2ace014c8a86f0aa635d947ac9febe83ef4e55966144b\
+                         2a5ab39dc13814b94e3ab6e101a34f27",
+                sha512: "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39\
+                         bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb",
+            },
+            Vector {
+                key: vec![0xaa; 131],
+                data: b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+                sha256: "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+                sha384: "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c6\
+                         0c2ef6ab4030fe8296248df163f44952",
+                sha512: "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+                         6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            },
+        ]
+    }
+
+    #[test]
+    fn rfc4231() {
+        for v in vectors() {
+            for (algorithm, expected) in [
+                (HashAlgorithm::Sha256, v.sha256),
+                (HashAlgorithm::Sha384, v.sha384),
+                (HashAlgorithm::Sha512, v.sha512),
+            ] {
+                let tag = Hmac::mac(algorithm, &v.key, &v.data).unwrap();
+                assert_eq!(hex::encode(tag), expected, "{algorithm:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_and_verify() {
+        let v = &vectors()[2];
+        let expected = hex::decode(v.sha256).unwrap();
+
+        let mut hmac = Hmac::new(HashAlgorithm::Sha256, &v.key).unwrap();
+        for chunk in v.data.chunks(7) {
+            hmac.update(chunk).unwrap();
+        }
+        assert!(hmac.verify(&expected).unwrap());
+
+        let mut tampered = expected.clone();
+        tampered[0] ^= 1;
+        let mut hmac = Hmac::new(HashAlgorithm::Sha256, &v.key).unwrap();
+        hmac.update(&v.data).unwrap();
+        assert!(!hmac.verify(&tampered).unwrap());
+
+        let hmac = Hmac::new(HashAlgorithm::Sha256, &v.key).unwrap();
+        assert!(!hmac.verify(&expected[..16]).unwrap());
+    }
+}
@@ -0,0 +1,563 @@
+//! Hybrid Public Key Encryption (RFC 9180), base mode.
+//!
+//! Requires the `hpke` feature and a wolfSSL build with `HAVE_HPKE`.
+
+use std::mem::MaybeUninit;
+use std::os::raw::{c_int, c_void};
+use std::ptr::NonNull;
+
+use zeroize::Zeroizing;
+
+use crate::curve25519::{Curve25519Key, X25519PrivateKey};
+use crate::ecc::{EccCurve, EccKey, EccKeyObject};
+use crate::error::WolfCryptError;
+use crate::rng::WolfRng;
+
+/// The length in bytes of the authentication tag added by every supported
+/// AEAD.
+pub const TAG_LEN: usize = 16;
+
+/// A key encapsulation mechanism.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HpkeKem {
+    /// DHKEM(P-256, HKDF-SHA256)
+    P256HkdfSha256,
+    /// DHKEM(P-384, HKDF-SHA384)
+    P384HkdfSha384,
+    /// DHKEM(P-521, HKDF-SHA512)
+    P521HkdfSha512,
+    /// DHKEM(X25519, HKDF-SHA256)
+    X25519HkdfSha256,
+}
+
+impl HpkeKem {
+    /// Returns the length in bytes of an encoded public key, which is also
+    /// the length of the encapsulated key (`Npk` and `Nenc`).
+    pub fn public_key_len(self) -> usize {
+        match self {
+            Self::P256HkdfSha256 => 65,
+            Self::P384HkdfSha384 => 97,
+            Self::P521HkdfSha512 => 133,
+            Self::X25519HkdfSha256 => 32,
+        }
+    }
+
+    /// Returns the RFC 9180 identifier, which wolfCrypt uses as is.
+    fn id(self) -> u16 {
+        match self {
+            Self::P256HkdfSha256 => 0x0010,
+            Self::P384HkdfSha384 => 0x0011,
+            Self::P521HkdfSha512 => 0x0012,
+            Self::X25519HkdfSha256 => 0x0020,
+        }
+    }
+
+    /// Returns the curve of the NIST DHKEMs.
+    fn curve(self) -> Option<EccCurve> {
+        match self {
+            Self::P256HkdfSha256 => Some(EccCurve::P256),
+            Self::P384HkdfSha384 => Some(EccCurve::P384),
+            Self::P521HkdfSha512 => Some(EccCurve::P521),
+            Self::X25519HkdfSha256 => None,
+        }
+    }
+}
+
+/// A key derivation function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HpkeKdf {
+    /// HKDF-SHA256
+    HkdfSha256,
+    /// HKDF-SHA384
+    HkdfSha384,
+    /// HKDF-SHA512
+    HkdfSha512,
+}
+
+impl HpkeKdf {
+    /// Returns the RFC 9180 identifier, which wolfCrypt uses as is.
+    fn id(self) -> u16 {
+        match self {
+            Self::HkdfSha256 => 0x0001,
+            Self::HkdfSha384 => 0x0002,
+            Self::HkdfSha512 => 0x0003,
+        }
+    }
+}
+
+/// An AEAD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HpkeAead {
+    /// AES-128-GCM
+    Aes128Gcm,
+    /// AES-256-GCM
+    Aes256Gcm,
+}
+
+impl HpkeAead {
+    /// Returns the RFC 9180 identifier, which wolfCrypt uses as is.
+    fn id(self) -> u16 {
+        match self {
+            Self::Aes128Gcm => 0x0001,
+            Self::Aes256Gcm => 0x0002,
+        }
+    }
+}
+
+/// A combination of KEM, KDF and AEAD. Sender and recipient must agree on
+/// all three.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HpkeSuite {
+    /// The key encapsulation mechanism.
+    pub kem: HpkeKem,
+    /// The key derivation function.
+    pub kdf: HpkeKdf,
+    /// The AEAD.
+    pub aead: HpkeAead,
+}
+
+/// The output of [`seal`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sealed {
+    /// The encapsulated key, which the recipient needs to open the message.
+    pub enc: Vec<u8>,
+    /// The ciphertext, [`TAG_LEN`] bytes longer than the plaintext.
+    pub ciphertext: Vec<u8>,
+}
+
+/// Encrypts `plaintext` to the holder of the private key for `recipient`,
+/// an encoded public key for `suite.kem`.
+///
+/// `info` binds the message to an application context and `aad` is
+/// authenticated but not encrypted; both must be repeated when opening.
+pub fn seal(
+    suite: HpkeSuite,
+    recipient: &[u8],
+    info: &[u8],
+    aad: &[u8],
+    plaintext: &[u8],
+    rng: &mut WolfRng,
+) -> Result<Sealed, WolfCryptError> {
+    let mut hpke = HpkeContext::new(suite)?;
+    let recipient = hpke.deserialize_public_key(recipient)?;
+    let ephemeral = hpke.generate_key(rng)?;
+
+    let mut ciphertext = vec![0u8; plaintext.len() + TAG_LEN];
+
+    // wolfCrypt takes the inputs as mutable pointers but only reads them.
+    // SAFETY: Both keys belong to `hpke`'s KEM, the inputs are valid for
+    // their lengths and `ciphertext` has room for the plaintext and tag.
+    WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_HpkeSealBase(
+            &mut *hpke.hpke,
+            ephemeral.0.as_ptr(),
+            recipient.0.as_ptr(),
+            info.as_ptr().cast_mut(),
+            len_u32(info)?,
+            aad.as_ptr().cast_mut(),
+            len_u32(aad)?,
+            plaintext.as_ptr().cast_mut(),
+            len_u32(plaintext)?,
+            ciphertext.as_mut_ptr(),
+        )
+    })?;
+
+    Ok(Sealed {
+        enc: hpke.serialize_public_key(&ephemeral)?,
+        ciphertext,
+    })
+}
+
+/// A recipient key pair.
+///
+/// wolfCrypt cannot import HPKE private keys itself, so long-lived key pairs
+/// are built from an [`X25519PrivateKey`] or [`EccKey`], which can be
+/// persisted.
+pub struct HpkeKeyPair {
+    suite: HpkeSuite,
+    key: HpkeKey,
+    public: Vec<u8>,
+}
+
+// SAFETY: The underlying key object is exclusively owned by this value and
+// carries no thread affinity.
+unsafe impl Send for HpkeKeyPair {}
+
+impl HpkeKeyPair {
+    /// Generates a key pair for `suite`.
+    pub fn generate(suite: HpkeSuite, rng: &mut WolfRng) -> Result<Self, WolfCryptError> {
+        let mut hpke = HpkeContext::new(suite)?;
+        let key = hpke.generate_key(rng)?;
+        let public = hpke.serialize_public_key(&key)?;
+
+        Ok(Self { suite, key, public })
+    }
+
+    /// Uses `key` as the recipient key pair for `suite`, whose KEM must be
+    /// [`HpkeKem::X25519HkdfSha256`].
+    pub fn from_x25519(suite: HpkeSuite, key: &X25519PrivateKey) -> Result<Self, WolfCryptError> {
+        if suite.kem != HpkeKem::X25519HkdfSha256 {
+            return Err(WolfCryptError::BAD_FUNC_ARG);
+        }
+
+        Self::from_key(suite, HpkeKey::from_curve25519(key.load()?))
+    }
+
+    /// Uses `key` as the recipient key pair for `suite`, whose KEM must be
+    /// the DHKEM for the key's curve.
+    pub fn from_ecc(suite: HpkeSuite, key: &EccKey) -> Result<Self, WolfCryptError> {
+        if suite.kem.curve() != Some(key.curve()) {
+            return Err(WolfCryptError::BAD_FUNC_ARG);
+        }
+
+        Self::from_key(suite, HpkeKey::from_ecc(key.load()?))
+    }
+
+    fn from_key(suite: HpkeSuite, key: HpkeKey) -> Result<Self, WolfCryptError> {
+        let public = HpkeContext::new(suite)?.serialize_public_key(&key)?;
+
+        Ok(Self { suite, key, public })
+    }
+
+    /// Returns the suite the key pair is for.
+    pub fn suite(&self) -> HpkeSuite {
+        self.suite
+    }
+
+    /// Returns the encoded public key, to be handed to senders.
+    pub fn public_key(&self) -> &[u8] {
+        &self.public
+    }
+
+    /// Decrypts a message produced by [`seal`] with this key pair's public
+    /// key and the same `info` and `aad`.
+    pub fn open(
+        &self,
+        enc: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, WolfCryptError> {
+        let plaintext_len = ciphertext
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(WolfCryptError::BAD_FUNC_ARG)?;
+        let enc_len = u16::try_from(enc.len()).map_err(|_| WolfCryptError::BAD_FUNC_ARG)?;
+
+        let mut hpke = HpkeContext::new(self.suite)?;
+        let mut plaintext = Zeroizing::new(vec![0u8; plaintext_len]);
+
+        // wolfCrypt's `ctSz` excludes the tag, which it reads from just past
+        // the end of the ciphertext proper.
+        let ct_len = len_u32(&plaintext)?;
+
+        // wolfCrypt takes the inputs as mutable pointers but only reads them.
+        // SAFETY: `self.key` belongs to `hpke`'s KEM and the inputs are valid
+        // for their lengths. `ciphertext` holds `plaintext_len` bytes plus the
+        // tag, and `plaintext` is writable for `plaintext_len` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_HpkeOpenBase(
+                &mut *hpke.hpke,
+                self.key.0.as_ptr(),
+                enc.as_ptr(),
+                enc_len,
+                info.as_ptr().cast_mut(),
+                len_u32(info)?,
+                aad.as_ptr().cast_mut(),
+                len_u32(aad)?,
+                ciphertext.as_ptr().cast_mut(),
+                ct_len,
+                plaintext.as_mut_ptr(),
+            )
+        })?;
+
+        Ok(plaintext)
+    }
+}
+
+impl std::fmt::Debug for HpkeKeyPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HpkeKeyPair")
+            .field("suite", &self.suite)
+            .finish_non_exhaustive()
+    }
+}
+
+fn len_u32(buf: &[u8]) -> Result<u32, WolfCryptError> {
+    u32::try_from(buf.len()).map_err(|_| WolfCryptError::BAD_FUNC_ARG)
+}
+
+/// An initialized wolfCrypt `Hpke` context.
+struct HpkeContext {
+    hpke: Box<wolfssl_sys::Hpke>,
+    kem: HpkeKem,
+}
+
+impl HpkeContext {
+    fn new(suite: HpkeSuite) -> Result<Self, WolfCryptError> {
+        let mut hpke = Box::new(MaybeUninit::<wolfssl_sys::Hpke>::uninit());
+
+        // SAFETY: `hpke` is writable for an `Hpke`; a null heap hint selects
+        // the default heap.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_HpkeInit(
+                hpke.as_mut_ptr(),
+                c_int::from(suite.kem.id()),
+                c_int::from(suite.kdf.id()),
+                c_int::from(suite.aead.id()),
+                std::ptr::null_mut(),
+            )
+        })?;
+
+        // SAFETY: `wc_HpkeInit` succeeded. `Hpke` owns no resources, so it
+        // needs no free function.
+        Ok(Self {
+            hpke: unsafe { hpke.assume_init() },
+            kem: suite.kem,
+        })
+    }
+
+    fn generate_key(&mut self, rng: &mut WolfRng) -> Result<HpkeKey, WolfCryptError> {
+        let mut key: *mut c_void = std::ptr::null_mut();
+
+        // SAFETY: `self.hpke` and `rng` are initialized. On success `key` points
+        // to a newly allocated key object.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_HpkeGenerateKeyPair(&mut *self.hpke, &mut key, rng.as_mut_ptr())
+        })?;
+
+        HpkeKey::new(key, self.kem.id())
+    }
+
+    fn deserialize_public_key(&mut self, bytes: &[u8]) -> Result<HpkeKey, WolfCryptError> {
+        let len = u16::try_from(bytes.len()).map_err(|_| WolfCryptError::BAD_FUNC_ARG)?;
+        let mut key: *mut c_void = std::ptr::null_mut();
+
+        // SAFETY: `self.hpke` is initialized and `bytes` is valid for `len`
+        // bytes. On success `key` points to a newly allocated key object.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_HpkeDeserializePublicKey(&mut *self.hpke, &mut key, bytes.as_ptr(), len)
+        })?;
+
+        HpkeKey::new(key, self.kem.id())
+    }
+
+    fn serialize_public_key(&mut self, key: &HpkeKey) -> Result<Vec<u8>, WolfCryptError> {
+        let mut out = vec![0u8; self.kem.public_key_len()];
+        let mut out_len = out.len() as u16;
+
+        // SAFETY: `key` belongs to `self.hpke`'s KEM and `out` is writable for
+        // `out_len` bytes.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_HpkeSerializePublicKey(
+                &mut *self.hpke,
+                key.0.as_ptr(),
+                out.as_mut_ptr(),
+                &mut out_len,
+            )
+        })?;
+        out.truncate(out_len as usize);
+
+        Ok(out)
+    }
+}
+
+/// A key object for a KEM; freeing it scrubs the key material.
+struct HpkeKey(NonNull<c_void>, KeyOwner);
+
+/// Where an [`HpkeKey`]'s object came from, which decides how it is freed.
+#[derive(Clone, Copy)]
+enum KeyOwner {
+    /// Allocated by wolfCrypt for the KEM with this id.
+    WolfCrypt(u16),
+    /// A leaked `Box<Curve25519Key>`.
+    Curve25519,
+    /// A leaked `Box<EccKeyObject>`.
+    Ecc,
+}
+
+impl HpkeKey {
+    fn new(key: *mut c_void, kem: u16) -> Result<Self, WolfCryptError> {
+        NonNull::new(key)
+            .map(|key| Self(key, KeyOwner::WolfCrypt(kem)))
+            .ok_or(WolfCryptError::BAD_FUNC_ARG)
+    }
+
+    fn from_curve25519(key: Box<Curve25519Key>) -> Self {
+        Self(NonNull::from(Box::leak(key)).cast(), KeyOwner::Curve25519)
+    }
+
+    fn from_ecc(key: Box<EccKeyObject>) -> Self {
+        Self(NonNull::from(Box::leak(key)).cast(), KeyOwner::Ecc)
+    }
+}
+
+impl Drop for HpkeKey {
+    fn drop(&mut self) {
+        let key = self.0.as_ptr();
+
+        match self.1 {
+            // SAFETY: `key` was allocated by wolfCrypt for KEM `kem` and is
+            // not used again. wolfCrypt only uses the KEM id and the heap
+            // hint, not the `Hpke` context.
+            KeyOwner::WolfCrypt(kem) => unsafe {
+                wolfssl_sys::wc_HpkeFreeKey(std::ptr::null_mut(), kem, key, std::ptr::null_mut())
+            },
+            // SAFETY: `key` was leaked from a `Box<Curve25519Key>` and is not
+            // used again. Dropping the box frees the key object.
+            KeyOwner::Curve25519 => drop(unsafe { Box::from_raw(key.cast::<Curve25519Key>()) }),
+            // SAFETY: `key` was leaked from a `Box<EccKeyObject>` and is not
+            // used again. Dropping the box frees the key object.
+            KeyOwner::Ecc => drop(unsafe { Box::from_raw(key.cast::<EccKeyObject>()) }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUITES: [HpkeSuite; 2] = [
+        HpkeSuite {
+            kem: HpkeKem::X25519HkdfSha256,
+            kdf: HpkeKdf::HkdfSha256,
+            aead: HpkeAead::Aes128Gcm,
+        },
+        HpkeSuite {
+            kem: HpkeKem::P256HkdfSha256,
+            kdf: HpkeKdf::HkdfSha256,
+            aead: HpkeAead::Aes256Gcm,
+        },
+    ];
+
+    #[test]
+    fn seal_open_round_trip() {
+        let mut rng = WolfRng::new().unwrap();
+
+        for suite in SUITES {
+            let recipient = HpkeKeyPair::generate(suite, &mut rng).unwrap();
+            assert_eq!(recipient.public_key().len(), suite.kem.public_key_len());
+
+            let sealed = seal(
+                suite,
+                recipient.public_key(),
+                b"info",
+                b"aad",
+                b"attack at dawn",
+                &mut rng,
+            )
+            .unwrap();
+            assert_eq!(sealed.enc.len(), suite.kem.public_key_len());
+            assert_eq!(sealed.ciphertext.len(), 14 + TAG_LEN);
+
+            let opened = recipient
+                .open(&sealed.enc, b"info", b"aad", &sealed.ciphertext)
+                .unwrap();
+            assert_eq!(&opened[..], b"attack at dawn");
+        }
+    }
+
+    #[test]
+    fn open_rejects_tampering() {
+        let mut rng = WolfRng::new().unwrap();
+        let suite = SUITES[0];
+        let recipient = HpkeKeyPair::generate(suite, &mut rng).unwrap();
+        let sealed = seal(
+            suite,
+            recipient.public_key(),
+            b"info",
+            b"aad",
+            b"msg",
+            &mut rng,
+        )
+        .unwrap();
+
+        let mut ciphertext = sealed.ciphertext.clone();
+        *ciphertext.last_mut().unwrap() ^= 1;
+        assert!(recipient
+            .open(&sealed.enc, b"info", b"aad", &ciphertext)
+            .is_err());
+        assert!(recipient
+            .open(&sealed.enc, b"info", b"other", &sealed.ciphertext)
+            .is_err());
+        assert!(recipient
+            .open(&sealed.enc, b"other", b"aad", &sealed.ciphertext)
+            .is_err());
+        assert!(recipient
+            .open(
+                &sealed.enc,
+                b"info",
+                b"aad",
+                &sealed.ciphertext[..TAG_LEN - 1]
+            )
+            .is_err());
+    }
+
+    /// RFC 9180 appendix A.1.1: DHKEM(X25519, HKDF-SHA256), HKDF-SHA256,
+    /// AES-128-GCM, base mode, sequence number 0.
+    #[test]
+    fn rfc9180_open() {
+        let sk_r = hex::decode("4612c550263fc8ad58375df3f557aac531d26850903e55a9f23f21d8534e8ac8")
+            .unwrap();
+        let recipient = HpkeKeyPair::from_x25519(
+            SUITES[0],
+            &X25519PrivateKey::from_bytes(sk_r.try_into().unwrap()),
+        )
+        .unwrap();
+        assert_eq!(
+            recipient.public_key(),
+            hex::decode("3948cfe0ad1ddb695d780e59077195da6c56506b207329794b8e2b1a4c8a5f3f")
+                .unwrap()
+        );
+
+        let enc = hex::decode("37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431")
+            .unwrap();
+        let ciphertext = hex::decode(
+            "f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a9\
+             6d8770ac83d07bea87e13c512a",
+        )
+        .unwrap();
+
+        let plaintext = recipient
+            .open(&enc, b"Ode on a Grecian Urn", b"Count-0", &ciphertext)
+            .unwrap();
+        assert_eq!(&plaintext[..], b"Beauty is truth, truth beauty");
+    }
+
+    #[test]
+    fn imported_key_pairs_open() {
+        let mut rng = WolfRng::new().unwrap();
+
+        let x25519 = X25519PrivateKey::generate(&mut rng).unwrap();
+        let ecc = EccKey::generate(EccCurve::P256, &mut rng).unwrap();
+        let recipients = [
+            HpkeKeyPair::from_x25519(SUITES[0], &x25519).unwrap(),
+            HpkeKeyPair::from_ecc(SUITES[1], &ecc).unwrap(),
+        ];
+        assert_eq!(
+            recipients[0].public_key(),
+            x25519.public_key().unwrap().as_bytes()
+        );
+        assert_eq!(recipients[1].public_key(), ecc.public_key().to_x963());
+
+        for recipient in recipients {
+            let suite = recipient.suite();
+            let sealed = seal(suite, recipient.public_key(), b"", b"", b"msg", &mut rng).unwrap();
+            let opened = recipient
+                .open(&sealed.enc, b"", b"", &sealed.ciphertext)
+                .unwrap();
+            assert_eq!(&opened[..], b"msg");
+        }
+    }
+
+    #[test]
+    fn imported_key_must_match_kem() {
+        let mut rng = WolfRng::new().unwrap();
+        let x25519 = X25519PrivateKey::generate(&mut rng).unwrap();
+        let ecc = EccKey::generate(EccCurve::P384, &mut rng).unwrap();
+
+        assert!(HpkeKeyPair::from_x25519(SUITES[1], &x25519).is_err());
+        assert!(HpkeKeyPair::from_ecc(SUITES[0], &ecc).is_err());
+        assert!(HpkeKeyPair::from_ecc(SUITES[1], &ecc).is_err());
+    }
+}
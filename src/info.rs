@@ -0,0 +1,120 @@
+//! Introspection of the linked wolfSSL library.
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+
+use crate::ecc::EccCurve;
+
+/// Returns the version string of the linked wolfSSL, e.g. `"5.7.2"`.
+pub fn version() -> &'static str {
+    // SAFETY: wolfSSL returns a pointer to a static, NUL terminated string.
+    let version = unsafe { CStr::from_ptr(wolfssl_sys::wolfSSL_lib_version()) };
+    version.to_str().unwrap_or("unknown")
+}
+
+/// A cipher suite, identified by wolfSSL's name for it (e.g.
+/// `TLS13-AES256-GCM-SHA384` or `ECDHE-RSA-AES128-GCM-SHA256`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CipherSuite(String);
+
+impl CipherSuite {
+    /// Returns wolfSSL's name for the suite, as accepted in cipher lists.
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns whether this is a TLS 1.3 suite.
+    pub fn is_tls13(&self) -> bool {
+        self.0.starts_with("TLS13-")
+    }
+}
+
+impl std::fmt::Display for CipherSuite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Returns every cipher suite the linked wolfSSL was compiled with, in its
+/// default preference order.
+///
+/// Which of these a connection may negotiate further depends on its
+/// protocol version, key types and configured cipher list.
+pub fn available_cipher_suites() -> Vec<CipherSuite> {
+    let mut buf = vec![0u8; 4096];
+
+    loop {
+        // SAFETY: `buf` is writable for its length, which wolfSSL respects
+        // and NUL terminates within.
+        let ret = unsafe {
+            wolfssl_sys::wolfSSL_get_ciphers(buf.as_mut_ptr().cast::<c_char>(), buf.len() as c_int)
+        };
+        if ret == wolfssl_sys::WOLFSSL_SUCCESS as c_int {
+            break;
+        }
+        if buf.len() >= 1 << 20 {
+            return Vec::new();
+        }
+        buf.resize(buf.len() * 2, 0);
+    }
+
+    let list = CStr::from_bytes_until_nul(&buf).unwrap_or_default();
+    list.to_string_lossy()
+        .split(':')
+        .filter(|name| !name.is_empty())
+        .map(|name| CipherSuite(name.to_owned()))
+        .collect()
+}
+
+/// What the linked wolfSSL was compiled with, as far as can be determined at
+/// runtime.
+///
+/// Capabilities that wolfSSL offers no runtime probe for are reported from
+/// this crate's own features, which must match the wolfssl-sys build.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct BuildFeatures {
+    /// `wolfSSL_lib_version_hex()`, e.g. `0x05007002` for 5.7.2.
+    pub version_hex: u32,
+    /// TLS 1.3 cipher suites are available.
+    pub tls13: bool,
+    /// Cipher suites for TLS 1.2 and earlier are available.
+    pub tls12: bool,
+    /// NIST P-256 is available.
+    pub p256: bool,
+    /// NIST P-384 is available.
+    pub p384: bool,
+    /// NIST P-521 is available.
+    pub p521: bool,
+    /// X448 and Ed448 are available (the `curve448` feature).
+    pub curve448: bool,
+    /// HPKE is available (the `hpke` feature).
+    pub hpke: bool,
+    /// The library is a FIPS build.
+    pub fips: bool,
+}
+
+impl BuildFeatures {
+    /// Probes the linked library.
+    pub fn detect() -> Self {
+        let ciphers = available_cipher_suites();
+
+        Self {
+            // SAFETY: No preconditions.
+            version_hex: unsafe { wolfssl_sys::wolfSSL_lib_version_hex() },
+            tls13: ciphers.iter().any(CipherSuite::is_tls13),
+            tls12: ciphers.iter().any(|suite| !suite.is_tls13()),
+            p256: curve_available(EccCurve::P256),
+            p384: curve_available(EccCurve::P384),
+            p521: curve_available(EccCurve::P521),
+            curve448: cfg!(feature = "curve448"),
+            hpke: cfg!(feature = "hpke"),
+            fips: crate::fips::is_enabled(),
+        }
+    }
+}
+
+fn curve_available(curve: EccCurve) -> bool {
+    // SAFETY: No preconditions.
+    unsafe { wolfssl_sys::wc_ecc_get_curve_idx(curve.wc_id()) >= 0 }
+}
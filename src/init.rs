@@ -0,0 +1,37 @@
+//! Library initialization.
+
+use crate::error::WolfCryptError;
+
+/// Keeps wolfSSL initialized for as long as it is alive.
+///
+/// wolfSSL counts initializations, so guards may be obtained independently
+/// by several components; the library is only cleaned up once the last one
+/// is dropped.
+#[derive(Debug)]
+#[must_use = "wolfSSL is cleaned up again as soon as the guard is dropped"]
+pub struct WolfSslGuard(());
+
+/// Initializes wolfSSL, returning a guard that cleans it up on drop.
+///
+/// With the `fips` feature, this fails if the FIPS module is in an error
+/// state.
+pub fn wolf_init() -> Result<WolfSslGuard, WolfCryptError> {
+    // SAFETY: No preconditions; wolfSSL serializes initialization.
+    WolfCryptError::check(unsafe { wolfssl_sys::wolfSSL_Init() })?;
+    let guard = WolfSslGuard(());
+
+    #[cfg(feature = "fips")]
+    if let Err(crate::fips::FipsError::Module(err)) = crate::fips::status() {
+        return Err(err);
+    }
+
+    Ok(guard)
+}
+
+impl Drop for WolfSslGuard {
+    fn drop(&mut self) {
+        // SAFETY: Balances the successful `wolfSSL_Init` that created this
+        // guard.
+        unsafe { wolfssl_sys::wolfSSL_Cleanup() };
+    }
+}
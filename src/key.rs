@@ -0,0 +1,293 @@
+//! Parsing and normalization of private keys.
+
+use std::mem::MaybeUninit;
+use std::os::raw::c_int;
+
+use zeroize::Zeroizing;
+
+// wolfSSL's `Key_Sum` values, as returned by `wc_GetPkcs8TraditionalOffset`.
+const RSA_K: c_int = wolfssl_sys::Key_Sum_RSAk as c_int;
+const ECDSA_K: c_int = wolfssl_sys::Key_Sum_ECDSAk as c_int;
+const ED25519_K: c_int = wolfssl_sys::Key_Sum_ED25519k as c_int;
+const ED448_K: c_int = wolfssl_sys::Key_Sum_ED448k as c_int;
+
+/// Errors that can occur when parsing a private key.
+#[derive(Debug, thiserror::Error)]
+pub enum KeyError {
+    /// The input is not a DER encoded private key in any supported format.
+    #[error("could not parse private key")]
+    Parse,
+    /// The key uses an algorithm this crate does not support.
+    #[error("unsupported private key algorithm (wolfSSL OID sum {0})")]
+    UnsupportedAlgorithm(i32),
+}
+
+/// The public key algorithm of a [`PrivateKey`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAlgorithm {
+    /// RSA. Stored as a PKCS#1 `RSAPrivateKey`.
+    Rsa,
+    /// Elliptic curve (NIST or Brainpool). Stored as a SEC1 `ECPrivateKey`.
+    Ecc,
+    /// Ed25519. Stored as the raw `CurvePrivateKey` octet string.
+    Ed25519,
+    /// Ed448. Stored as the raw `CurvePrivateKey` octet string.
+    Ed448,
+}
+
+/// A DER encoded private key, normalized to its algorithm specific
+/// ("traditional") encoding regardless of how it was supplied.
+///
+/// The encoding is zeroized on drop.
+///
+/// wolfSSL's `*_use_PrivateKey_buffer` functions accept this encoding with
+/// `WOLFSSL_FILETYPE_ASN1`.
+#[derive(Clone)]
+pub struct PrivateKey {
+    algorithm: KeyAlgorithm,
+    der: Zeroizing<Vec<u8>>,
+}
+
+impl PrivateKey {
+    /// Parses a PKCS#8 `PrivateKeyInfo` and strips the PKCS#8 wrapper.
+    ///
+    /// EC keys are re-encoded so that the resulting `ECPrivateKey` names
+    /// its curve.
+    pub fn from_pkcs8(der: &[u8]) -> Result<Self, KeyError> {
+        // wolfSSL takes a mutable pointer even though it only reads.
+        let mut input = Zeroizing::new(der.to_vec());
+        let mut offset: u32 = 0;
+
+        // SAFETY: `input` is valid for its length, and wolfSSL only advances
+        // `offset` within it.
+        let alg = unsafe {
+            wolfssl_sys::wc_GetPkcs8TraditionalOffset(
+                input.as_mut_ptr(),
+                &mut offset,
+                input.len() as u32,
+            )
+        };
+        if alg < 0 {
+            return Err(KeyError::Parse);
+        }
+
+        let algorithm = match alg {
+            RSA_K => KeyAlgorithm::Rsa,
+            ECDSA_K => KeyAlgorithm::Ecc,
+            ED25519_K => KeyAlgorithm::Ed25519,
+            ED448_K => KeyAlgorithm::Ed448,
+            other => return Err(KeyError::UnsupportedAlgorithm(other)),
+        };
+
+        let der = match algorithm {
+            // The curve is only named in the PKCS#8 `AlgorithmIdentifier`,
+            // so re-encode the key rather than strip the wrapper. Otherwise
+            // wolfSSL would guess the curve from the scalar's length.
+            KeyAlgorithm::Ecc => ecc_pkcs8_to_sec1(&input).ok_or(KeyError::Parse)?,
+            _ => {
+                input.drain(..offset as usize);
+                input
+            }
+        };
+
+        Ok(Self { algorithm, der })
+    }
+
+    /// Parses a DER encoded private key that may be PKCS#8, PKCS#1 (RSA) or
+    /// SEC1 (EC).
+    ///
+    /// A well formed PKCS#8 key for an unsupported algorithm is reported as
+    /// [`KeyError::UnsupportedAlgorithm`] rather than [`KeyError::Parse`].
+    pub fn from_der(der: &[u8]) -> Result<Self, KeyError> {
+        match Self::from_pkcs8(der) {
+            Err(KeyError::Parse) => {}
+            result => return result,
+        }
+
+        let algorithm = if is_rsa_private_key(der) {
+            KeyAlgorithm::Rsa
+        } else if is_ecc_private_key(der) {
+            KeyAlgorithm::Ecc
+        } else {
+            return Err(KeyError::Parse);
+        };
+
+        Ok(Self {
+            algorithm,
+            der: Zeroizing::new(der.to_vec()),
+        })
+    }
+
+    /// Wraps a key that is already in its algorithm specific encoding.
+    pub(crate) fn from_parts(algorithm: KeyAlgorithm, der: Zeroizing<Vec<u8>>) -> Self {
+        Self { algorithm, der }
+    }
+
+    /// Returns the key's algorithm.
+    pub fn algorithm(&self) -> KeyAlgorithm {
+        self.algorithm
+    }
+
+    /// Returns the key in its algorithm specific DER encoding.
+    pub fn as_der(&self) -> &[u8] {
+        &self.der
+    }
+}
+
+impl std::fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrivateKey")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+fn is_rsa_private_key(der: &[u8]) -> bool {
+    let mut key = MaybeUninit::<wolfssl_sys::RsaKey>::uninit();
+
+    // SAFETY: `key` is writable; a null heap hint selects the default heap.
+    if unsafe { wolfssl_sys::wc_InitRsaKey(key.as_mut_ptr(), std::ptr::null_mut()) } != 0 {
+        return false;
+    }
+
+    let mut idx: u32 = 0;
+
+    // SAFETY: `key` was initialized above and `der` is valid for its length.
+    let ret = unsafe {
+        wolfssl_sys::wc_RsaPrivateKeyDecode(
+            der.as_ptr(),
+            &mut idx,
+            key.as_mut_ptr(),
+            der.len() as u32,
+        )
+    };
+
+    // SAFETY: `key` was initialized above and is not used afterwards.
+    unsafe { wolfssl_sys::wc_FreeRsaKey(key.as_mut_ptr()) };
+
+    ret == 0
+}
+
+fn is_ecc_private_key(der: &[u8]) -> bool {
+    let mut key = MaybeUninit::<wolfssl_sys::ecc_key>::uninit();
+
+    // SAFETY: `key` is writable.
+    if unsafe { wolfssl_sys::wc_ecc_init(key.as_mut_ptr()) } != 0 {
+        return false;
+    }
+
+    let mut idx: u32 = 0;
+
+    // SAFETY: `key` was initialized above and `der` is valid for its length.
+    let ret = unsafe {
+        wolfssl_sys::wc_EccPrivateKeyDecode(
+            der.as_ptr(),
+            &mut idx,
+            key.as_mut_ptr(),
+            der.len() as u32,
+        )
+    };
+
+    // SAFETY: `key` was initialized above and is not used afterwards.
+    unsafe { wolfssl_sys::wc_ecc_free(key.as_mut_ptr()) };
+
+    ret == 0
+}
+
+fn ecc_pkcs8_to_sec1(der: &[u8]) -> Option<Zeroizing<Vec<u8>>> {
+    let mut key = MaybeUninit::<wolfssl_sys::ecc_key>::uninit();
+
+    // SAFETY: `key` is writable.
+    if unsafe { wolfssl_sys::wc_ecc_init(key.as_mut_ptr()) } != 0 {
+        return None;
+    }
+
+    let mut idx: u32 = 0;
+
+    // SAFETY: `key` was initialized above and `der` is valid for its length.
+    let mut ret = unsafe {
+        wolfssl_sys::wc_EccPrivateKeyDecode(
+            der.as_ptr(),
+            &mut idx,
+            key.as_mut_ptr(),
+            der.len() as u32,
+        )
+    };
+
+    // The SEC1 encoding drops the PKCS#8 wrapper but gains the curve, so
+    // this leaves ample room.
+    let mut sec1 = Zeroizing::new(vec![0u8; der.len() + 64]);
+    if ret == 0 {
+        // SAFETY: `key` holds the decoded key and `sec1` is writable for its
+        // length.
+        ret = unsafe {
+            wolfssl_sys::wc_EccKeyToDer(key.as_mut_ptr(), sec1.as_mut_ptr(), sec1.len() as u32)
+        };
+    }
+
+    // SAFETY: `key` was initialized above and is not used afterwards.
+    unsafe { wolfssl_sys::wc_ecc_free(key.as_mut_ptr()) };
+
+    let len = usize::try_from(ret).ok().filter(|&len| len > 0)?;
+    sec1.truncate(len);
+    Some(sec1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A P-256 key as SEC1 `ECPrivateKey`.
+    const SEC1: &str = "307702010104207e5f0c2f6262e3fec117181f4cddc8f79f93519cf56cbab2db6f4e\
+                        1f74a775bfa00a06082a8648ce3d030107a14403420004a9827e50bcc71d745d3e\
+                        cb1698feb0a5c06c147045da7be10281152147aaef4d0ea89d43a87c30d0994da7\
+                        af65ce599ee5468ef2a108f33567b394fda771988f";
+
+    /// The same key as PKCS#8, whose inner `ECPrivateKey` omits the curve.
+    /// It is only named in the `AlgorithmIdentifier`.
+    const PKCS8: &str = "308187020100301306072a8648ce3d020106082a8648ce3d030107046d306b0201\
+                         0104207e5f0c2f6262e3fec117181f4cddc8f79f93519cf56cbab2db6f4e1f74a7\
+                         75bfa14403420004a9827e50bcc71d745d3ecb1698feb0a5c06c147045da7be102\
+                         81152147aaef4d0ea89d43a87c30d0994da7af65ce599ee5468ef2a108f33567b3\
+                         94fda771988f";
+
+    /// An X25519 key as PKCS#8.
+    const X25519_PKCS8: &str = "302e020100300506032b656e042204205875ed59330ab43bc831e3a95bd2911a\
+                                0c5743425bc27c62930f7142d3858f7e";
+
+    /// The DER encoded OID of prime256v1 (P-256).
+    const P256_OID: &str = "06082a8648ce3d030107";
+
+    #[test]
+    fn pkcs8_ecc_keys_keep_their_curve() {
+        let pkcs8 = hex::decode(PKCS8).unwrap();
+        let key = PrivateKey::from_der(&pkcs8).unwrap();
+
+        assert_eq!(key.algorithm(), KeyAlgorithm::Ecc);
+        assert!(hex::encode(key.as_der()).contains(P256_OID));
+        assert_eq!(key.as_der(), hex::decode(SEC1).unwrap());
+    }
+
+    #[test]
+    fn accepts_traditional_encoding() {
+        let sec1 = hex::decode(SEC1).unwrap();
+        let key = PrivateKey::from_der(&sec1).unwrap();
+
+        assert_eq!(key.algorithm(), KeyAlgorithm::Ecc);
+        assert_eq!(key.as_der(), sec1);
+    }
+
+    #[test]
+    fn reports_unsupported_algorithms() {
+        let der = hex::decode(X25519_PKCS8).unwrap();
+
+        assert!(matches!(
+            PrivateKey::from_der(&der),
+            Err(KeyError::UnsupportedAlgorithm(_))
+        ));
+        assert!(matches!(
+            PrivateKey::from_der(b"not a key"),
+            Err(KeyError::Parse)
+        ));
+    }
+}
@@ -1 +1,30 @@
+//! High-level bindings for WolfSSL
 
+pub mod aes;
+pub mod ct;
+pub mod curve25519;
+#[cfg(feature = "curve448")]
+pub mod curve448;
+pub mod ecc;
+#[cfg(feature = "curve448")]
+pub mod ed448;
+mod error;
+pub mod fips;
+pub mod hash;
+pub mod hkdf;
+pub mod hmac;
+#[cfg(feature = "hpke")]
+pub mod hpke;
+mod info;
+mod init;
+pub mod key;
+pub mod logging;
+pub mod pbkdf2;
+pub mod pem;
+pub mod rng;
+pub mod rsa;
+pub mod x509;
+
+pub use error::WolfCryptError;
+pub use info::{available_cipher_suites, version, BuildFeatures, CipherSuite};
+pub use init::{wolf_init, WolfSslGuard};
@@ -0,0 +1,51 @@
+//! Forwarding of wolfSSL's internal debug output to the [`log`] crate.
+//!
+//! wolfSSL only produces this output when it was compiled with
+//! `DEBUG_WOLFSSL`; on other builds [`enable_debug_logging`] reports
+//! [`DebugLoggingError::NotCompiledIn`].
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+
+/// Errors that can occur when toggling wolfSSL's debug output.
+#[derive(Debug, thiserror::Error)]
+pub enum DebugLoggingError {
+    /// The linked wolfSSL was built without `DEBUG_WOLFSSL`.
+    #[error("wolfSSL was built without debug logging support")]
+    NotCompiledIn,
+}
+
+/// Starts routing wolfSSL's debug messages through [`log::debug!`].
+///
+/// Messages are emitted with this module's path as their target, so they
+/// can be filtered independently of the rest of the application.
+pub fn enable_debug_logging() -> Result<(), DebugLoggingError> {
+    // SAFETY: `wolfssl_log` has the signature wolfSSL expects and, being a
+    // plain function, lives for the remainder of the program.
+    if unsafe { wolfssl_sys::wolfSSL_SetLoggingCb(Some(wolfssl_log)) } != 0 {
+        return Err(DebugLoggingError::NotCompiledIn);
+    }
+
+    // SAFETY: No preconditions.
+    match unsafe { wolfssl_sys::wolfSSL_Debugging_ON() } {
+        0 => Ok(()),
+        _ => Err(DebugLoggingError::NotCompiledIn),
+    }
+}
+
+/// Stops wolfSSL from producing debug messages.
+pub fn disable_debug_logging() {
+    // SAFETY: No preconditions.
+    unsafe { wolfssl_sys::wolfSSL_Debugging_OFF() }
+}
+
+unsafe extern "C" fn wolfssl_log(_level: c_int, message: *const c_char) {
+    if message.is_null() {
+        return;
+    }
+
+    // SAFETY: wolfSSL hands us a NUL terminated string which stays valid for
+    // the duration of this call.
+    let message = unsafe { CStr::from_ptr(message) };
+    log::debug!("{}", message.to_string_lossy());
+}
@@ -0,0 +1,218 @@
+//! Conversion of keys and certificates between PEM and DER.
+//!
+//! Decoding skips any text before the first block of the expected kind, so
+//! these accept files with leading comments or several blocks.
+
+use std::os::raw::c_int;
+
+use zeroize::Zeroizing;
+
+use crate::error::WolfCryptError;
+
+/// The label to armor DER with.
+///
+/// Discriminants are wolfSSL `CertType` values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PemType {
+    /// `CERTIFICATE`
+    Certificate = wolfssl_sys::CertType_CERT_TYPE as isize,
+    /// `RSA PRIVATE KEY` (PKCS#1)
+    RsaPrivateKey = wolfssl_sys::CertType_PRIVATEKEY_TYPE as isize,
+    /// `X509 CRL`
+    Crl = wolfssl_sys::CertType_CRL_TYPE as isize,
+    /// `EC PRIVATE KEY` (SEC1)
+    EcPrivateKey = wolfssl_sys::CertType_ECC_PRIVATEKEY_TYPE as isize,
+    /// `CERTIFICATE REQUEST`
+    CertificateRequest = wolfssl_sys::CertType_CERTREQ_TYPE as isize,
+    /// `PUBLIC KEY` (SubjectPublicKeyInfo)
+    PublicKey = wolfssl_sys::CertType_PUBLICKEY_TYPE as isize,
+    /// `PRIVATE KEY` (unencrypted PKCS#8)
+    PrivateKey = wolfssl_sys::CertType_PKCS8_PRIVATEKEY_TYPE as isize,
+}
+
+/// Decodes the first `CERTIFICATE` block in `pem`.
+pub fn certificate_pem_to_der(pem: &[u8]) -> Result<Vec<u8>, WolfCryptError> {
+    let len = c_int::try_from(pem.len()).map_err(|_| WolfCryptError::BAD_FUNC_ARG)?;
+    let mut der = vec![0u8; pem.len()];
+
+    // SAFETY: `pem` is valid for `len` bytes and `der` is writable for as
+    // many.
+    let der_len = WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_CertPemToDer(
+            pem.as_ptr(),
+            len,
+            der.as_mut_ptr(),
+            len,
+            PemType::Certificate as c_int,
+        )
+    })?;
+    der.truncate(der_len as usize);
+
+    Ok(der)
+}
+
+/// Decodes the first unencrypted PEM private key in `pem` (any of the
+/// `PRIVATE KEY`, `RSA PRIVATE KEY` or `EC PRIVATE KEY` labels).
+pub fn private_key_pem_to_der(pem: &[u8]) -> Result<Zeroizing<Vec<u8>>, WolfCryptError> {
+    let len = c_int::try_from(pem.len()).map_err(|_| WolfCryptError::BAD_FUNC_ARG)?;
+
+    // The DER encoding is always shorter than its base64 armor.
+    let mut der = Zeroizing::new(vec![0u8; pem.len()]);
+
+    // SAFETY: `pem` is valid for `len` bytes and `der` is writable for as
+    // many. No password is supplied.
+    let der_len = WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_KeyPemToDer(pem.as_ptr(), len, der.as_mut_ptr(), len, std::ptr::null())
+    })?;
+    der.truncate(der_len as usize);
+
+    Ok(der)
+}
+
+/// Decodes the first `PUBLIC KEY` or `RSA PUBLIC KEY` block in `pem`.
+pub fn public_key_pem_to_der(pem: &[u8]) -> Result<Vec<u8>, WolfCryptError> {
+    let len = c_int::try_from(pem.len()).map_err(|_| WolfCryptError::BAD_FUNC_ARG)?;
+    let mut der = vec![0u8; pem.len()];
+
+    // SAFETY: `pem` is valid for `len` bytes and `der` is writable for as
+    // many.
+    let der_len = WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_PubKeyPemToDer(pem.as_ptr(), len, der.as_mut_ptr(), len)
+    })?;
+    der.truncate(der_len as usize);
+
+    Ok(der)
+}
+
+/// Armors `der` as PEM with the label selected by `ty`.
+///
+/// The intermediate buffer is zeroized, so this is suitable for private keys
+/// as long as the caller also zeroizes the result, e.g. by wrapping it in
+/// [`Zeroizing`].
+pub fn der_to_pem(der: &[u8], ty: PemType) -> Result<String, WolfCryptError> {
+    let der_len = u32::try_from(der.len()).map_err(|_| WolfCryptError::BAD_FUNC_ARG)?;
+
+    // Base64 expansion, a newline every 64 characters, and the two armor
+    // lines.
+    let mut pem = Zeroizing::new(vec![0u8; der.len() * 4 / 3 + der.len() / 48 + 128]);
+
+    // SAFETY: `der` is valid for `der_len` bytes and `pem` is writable for
+    // its length.
+    let pem_len = WolfCryptError::check(unsafe {
+        wolfssl_sys::wc_DerToPem(
+            der.as_ptr(),
+            der_len,
+            pem.as_mut_ptr(),
+            pem.len() as u32,
+            ty as c_int,
+        )
+    })?;
+    pem.truncate(pem_len as usize);
+
+    // Base64 and the armor lines are always ASCII.
+    String::from_utf8(std::mem::take(&mut *pem)).map_err(|_| WolfCryptError::BAD_FUNC_ARG)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TYPES: [(PemType, &str); 7] = [
+        (PemType::Certificate, "CERTIFICATE"),
+        (PemType::RsaPrivateKey, "RSA PRIVATE KEY"),
+        (PemType::Crl, "X509 CRL"),
+        (PemType::EcPrivateKey, "EC PRIVATE KEY"),
+        (PemType::CertificateRequest, "CERTIFICATE REQUEST"),
+        (PemType::PublicKey, "PUBLIC KEY"),
+        (PemType::PrivateKey, "PRIVATE KEY"),
+    ];
+
+    /// A DER `SEQUENCE` with `len` bytes of content.
+    fn der(len: usize) -> Vec<u8> {
+        let mut der = vec![0x30];
+        match len {
+            0..=0x7f => der.push(len as u8),
+            0x80..=0xff => der.extend([0x81, len as u8]),
+            _ => der.extend([0x82, (len >> 8) as u8, len as u8]),
+        }
+        der.extend((0..len).map(|i| i as u8));
+        der
+    }
+
+    /// Decodes `pem` with the function matching `ty`, if there is one.
+    fn decode(pem: &str, ty: PemType) -> Option<Vec<u8>> {
+        let pem = pem.as_bytes();
+        Some(match ty {
+            PemType::Certificate => certificate_pem_to_der(pem).unwrap(),
+            PemType::RsaPrivateKey | PemType::EcPrivateKey | PemType::PrivateKey => {
+                private_key_pem_to_der(pem).unwrap().to_vec()
+            }
+            PemType::PublicKey => public_key_pem_to_der(pem).unwrap(),
+            PemType::Crl | PemType::CertificateRequest => return None,
+        })
+    }
+
+    #[test]
+    fn round_trips_every_label() {
+        let der = der(100);
+
+        for (ty, label) in TYPES {
+            let pem = der_to_pem(&der, ty).unwrap();
+            assert!(
+                pem.starts_with(&format!("-----BEGIN {label}-----\n")),
+                "{ty:?}"
+            );
+            assert!(pem.ends_with(&format!("-----END {label}-----\n")), "{ty:?}");
+
+            if let Some(decoded) = decode(&pem, ty) {
+                assert_eq!(decoded, der, "{ty:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn output_buffer_fits_every_length() {
+        for len in 0..600 {
+            let der = der(len);
+            let pem = der_to_pem(&der, PemType::CertificateRequest).unwrap();
+            assert!(pem.lines().all(|line| line.len() <= 64), "{len}");
+
+            let pem = der_to_pem(&der, PemType::Certificate).unwrap();
+            assert_eq!(
+                certificate_pem_to_der(pem.as_bytes()).unwrap(),
+                der,
+                "{len}"
+            );
+        }
+    }
+
+    #[test]
+    fn skips_leading_text_and_other_blocks() {
+        let first = der(40);
+        let second = der(50);
+        let key = der(60);
+        let pem = format!(
+            "Subject: example\n{}{}{}{}",
+            der_to_pem(&key, PemType::PublicKey).unwrap(),
+            der_to_pem(&first, PemType::Certificate).unwrap(),
+            der_to_pem(&second, PemType::Certificate).unwrap(),
+            der_to_pem(&key, PemType::PrivateKey).unwrap(),
+        );
+
+        assert_eq!(certificate_pem_to_der(pem.as_bytes()).unwrap(), first);
+        assert_eq!(public_key_pem_to_der(pem.as_bytes()).unwrap(), key);
+        assert_eq!(*private_key_pem_to_der(pem.as_bytes()).unwrap(), key);
+    }
+
+    #[test]
+    fn rejects_wrong_label() {
+        let der = der(40);
+        let certificate = der_to_pem(&der, PemType::Certificate).unwrap();
+        let public_key = der_to_pem(&der, PemType::PublicKey).unwrap();
+
+        assert!(certificate_pem_to_der(public_key.as_bytes()).is_err());
+        assert!(public_key_pem_to_der(certificate.as_bytes()).is_err());
+        assert!(private_key_pem_to_der(certificate.as_bytes()).is_err());
+        assert!(private_key_pem_to_der(public_key.as_bytes()).is_err());
+    }
+}
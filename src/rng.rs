@@ -0,0 +1,283 @@
+//! wolfCrypt's random number generator (Hash-DRBG).
+
+use std::cell::RefCell;
+use std::os::raw::c_int;
+
+use crate::error::WolfCryptError;
+
+/// The largest request `wc_RNG_GenerateBlock` will serve in one call
+/// (`RNG_MAX_BLOCK_LEN`).
+const MAX_BLOCK_LEN: usize = wolfssl_sys::RNG_MAX_BLOCK_LEN as usize;
+
+// wolfCrypt error codes (`error-crypt.h`) reported by the entropy source.
+const OPEN_RAN_E: c_int = wolfssl_sys::wolfCrypt_ErrorCodes_OPEN_RAN_E;
+const READ_RAN_E: c_int = wolfssl_sys::wolfCrypt_ErrorCodes_READ_RAN_E;
+const WINCRYPT_E: c_int = wolfssl_sys::wolfCrypt_ErrorCodes_WINCRYPT_E;
+const CRYPTGEN_E: c_int = wolfssl_sys::wolfCrypt_ErrorCodes_CRYPTGEN_E;
+const RAN_BLOCK_E: c_int = wolfssl_sys::wolfCrypt_ErrorCodes_RAN_BLOCK_E;
+const ENTROPY_RT_E: c_int = wolfssl_sys::wolfCrypt_ErrorCodes_ENTROPY_RT_E;
+const ENTROPY_APT_E: c_int = wolfssl_sys::wolfCrypt_ErrorCodes_ENTROPY_APT_E;
+
+// wolfCrypt error codes reported by the DRBG's own health tests.
+const RNG_FAILURE_E: c_int = wolfssl_sys::wolfCrypt_ErrorCodes_RNG_FAILURE_E;
+const DRBG_CONT_FIPS_E: c_int = wolfssl_sys::wolfCrypt_ErrorCodes_DRBG_CONT_FIPS_E;
+
+/// Errors reported by wolfCrypt's RNG.
+#[derive(Debug, thiserror::Error)]
+pub enum RngError {
+    /// The operating system entropy source could not be read or failed its
+    /// health tests.
+    #[error("entropy source failure: {0}")]
+    Entropy(WolfCryptError),
+    /// The DRBG failed a health or continuous test. The instance is unusable
+    /// and must be recreated.
+    #[error("DRBG failure: {0}")]
+    Drbg(WolfCryptError),
+    /// Any other wolfCrypt error.
+    #[error(transparent)]
+    Other(WolfCryptError),
+}
+
+impl From<WolfCryptError> for RngError {
+    fn from(e: WolfCryptError) -> Self {
+        match e.code() {
+            OPEN_RAN_E | READ_RAN_E | WINCRYPT_E | CRYPTGEN_E | RAN_BLOCK_E | ENTROPY_RT_E
+            | ENTROPY_APT_E => Self::Entropy(e),
+            RNG_FAILURE_E | DRBG_CONT_FIPS_E => Self::Drbg(e),
+            _ => Self::Other(e),
+        }
+    }
+}
+
+/// Fills `dest` with random bytes from a lazily created, per-thread
+/// [`WolfRng`].
+///
+/// If the DRBG reports a failure, the per-thread instance is discarded and
+/// a fresh one is created by the next call.
+pub fn random_bytes(dest: &mut [u8]) -> Result<(), RngError> {
+    thread_local! {
+        static RNG: RefCell<Option<WolfRng>> = const { RefCell::new(None) };
+    }
+
+    RNG.with_borrow_mut(|slot| {
+        let rng = match slot {
+            Some(rng) => rng,
+            None => slot.insert(WolfRng::new()?),
+        };
+
+        let res = rng.fill(dest);
+        if matches!(res, Err(RngError::Drbg(_))) {
+            *slot = None;
+        }
+        res
+    })
+}
+
+/// An instance of wolfCrypt's DRBG.
+///
+/// With the `rand_core` feature enabled this implements
+/// [`rand_core::RngCore`] and [`rand_core::CryptoRng`].
+pub struct WolfRng(Box<wolfssl_sys::WC_RNG>);
+
+// SAFETY: A `WC_RNG` has no thread affinity, and every operation on it goes
+// through `&mut self`.
+unsafe impl Send for WolfRng {}
+
+impl WolfRng {
+    /// Creates and seeds a new DRBG instance from the system entropy source.
+    pub fn new() -> Result<Self, RngError> {
+        // SAFETY: `rng` is writable for a `WC_RNG`.
+        Self::init(|rng| unsafe { wolfssl_sys::wc_InitRng(rng) })
+    }
+
+    /// Creates a new DRBG instance seeded from the system entropy source and
+    /// instantiated with `nonce` as additional input.
+    pub fn with_nonce(nonce: &[u8]) -> Result<Self, RngError> {
+        // wolfCrypt takes a mutable pointer even though it only reads.
+        let mut nonce = nonce.to_vec();
+
+        // SAFETY: `rng` is writable for a `WC_RNG`, and `nonce` is valid for
+        // its length.
+        Self::init(|rng| unsafe {
+            wolfssl_sys::wc_InitRngNonce(rng, nonce.as_mut_ptr(), nonce.len() as u32)
+        })
+    }
+
+    fn init(init: impl FnOnce(*mut wolfssl_sys::WC_RNG) -> c_int) -> Result<Self, RngError> {
+        // The DRBG state may be embedded in `WC_RNG`, so it must not move
+        // once initialized.
+        let mut rng = Box::<wolfssl_sys::WC_RNG>::new_uninit();
+
+        WolfCryptError::check(init(rng.as_mut_ptr()))?;
+
+        // SAFETY: Initialization succeeded, so `rng` is initialized.
+        Ok(Self(unsafe { rng.assume_init() }))
+    }
+
+    /// Fills `dest` with random bytes.
+    pub fn fill(&mut self, dest: &mut [u8]) -> Result<(), RngError> {
+        for chunk in dest.chunks_mut(MAX_BLOCK_LEN) {
+            // SAFETY: `self.0` is an initialized RNG and `chunk` is writable
+            // for its length.
+            WolfCryptError::check(unsafe {
+                wolfssl_sys::wc_RNG_GenerateBlock(
+                    &mut *self.0,
+                    chunk.as_mut_ptr(),
+                    chunk.len() as u32,
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying `WC_RNG` for passing to wolfCrypt.
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut wolfssl_sys::WC_RNG {
+        &mut *self.0
+    }
+
+    /// Reseeds the DRBG with caller supplied `entropy`.
+    ///
+    /// wolfCrypt reseeds from the system entropy source on its own schedule;
+    /// this is for callers that must inject entropy explicitly.
+    pub fn reseed(&mut self, entropy: &[u8]) -> Result<(), RngError> {
+        // SAFETY: `self.0` is an initialized RNG and `entropy` is valid for
+        // its length.
+        WolfCryptError::check(unsafe {
+            wolfssl_sys::wc_RNG_DRBG_Reseed(&mut *self.0, entropy.as_ptr(), entropy.len() as u32)
+        })?;
+        Ok(())
+    }
+}
+
+impl Drop for WolfRng {
+    fn drop(&mut self) {
+        // SAFETY: `self.0` is an initialized RNG which is not used again.
+        unsafe { wolfssl_sys::wc_FreeRng(&mut *self.0) };
+    }
+}
+
+impl std::fmt::Debug for WolfRng {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WolfRng").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::RngCore for WolfRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if let Err(e) = self.fill(dest) {
+            panic!("wolfCrypt RNG failure: {e}");
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill(dest).map_err(rand_core::Error::new)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::CryptoRng for WolfRng {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_zero(bytes: &[u8]) -> bool {
+        bytes.iter().all(|&b| b == 0)
+    }
+
+    #[test]
+    fn fill_spans_several_blocks() {
+        let mut rng = WolfRng::new().unwrap();
+        let mut buf = vec![0u8; 3 * MAX_BLOCK_LEN + 17];
+        rng.fill(&mut buf).unwrap();
+
+        for chunk in buf.chunks(MAX_BLOCK_LEN) {
+            assert!(!is_zero(chunk));
+        }
+
+        let mut other = vec![0u8; buf.len()];
+        rng.fill(&mut other).unwrap();
+        assert_ne!(buf, other);
+    }
+
+    #[test]
+    fn with_nonce() {
+        let mut a = WolfRng::with_nonce(b"nonce").unwrap();
+        let mut b = WolfRng::with_nonce(b"nonce").unwrap();
+        let mut buf_a = [0u8; 32];
+        let mut buf_b = [0u8; 32];
+        a.fill(&mut buf_a).unwrap();
+        b.fill(&mut buf_b).unwrap();
+
+        // The nonce is additional input, not a replacement for entropy.
+        assert!(!is_zero(&buf_a));
+        assert_ne!(buf_a, buf_b);
+    }
+
+    #[test]
+    fn reseed() {
+        let mut rng = WolfRng::new().unwrap();
+        let mut before = [0u8; 32];
+        rng.fill(&mut before).unwrap();
+
+        rng.reseed(&[0x5a; 48]).unwrap();
+
+        let mut after = [0u8; 32];
+        rng.fill(&mut after).unwrap();
+        assert!(!is_zero(&after));
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn thread_local_random_bytes() {
+        let mut a = [0u8; 64];
+        let mut b = [0u8; 64];
+        random_bytes(&mut a).unwrap();
+        random_bytes(&mut b).unwrap();
+        assert!(!is_zero(&a));
+        assert_ne!(a, b);
+
+        random_bytes(&mut []).unwrap();
+
+        let other = std::thread::spawn(|| {
+            let mut buf = [0u8; 64];
+            random_bytes(&mut buf).unwrap();
+            buf
+        })
+        .join()
+        .unwrap();
+        assert_ne!(a, other);
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn rand_core() {
+        use rand_core::RngCore;
+
+        let mut rng = WolfRng::new().unwrap();
+        let mut a = [0u8; 32];
+        let mut b = [0u8; 32];
+        rng.fill_bytes(&mut a);
+        rng.try_fill_bytes(&mut b).unwrap();
+
+        assert!(!is_zero(&a));
+        assert_ne!(a, b);
+        assert_ne!(
+            [rng.next_u64(), rng.next_u64()],
+            [rng.next_u64(), rng.next_u64()]
+        );
+        assert_ne!(
+            [rng.next_u32(), rng.next_u32(), rng.next_u32()],
+            [rng.next_u32(), rng.next_u32(), rng.next_u32()]
+        );
+    }
+}
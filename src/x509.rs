@@ -0,0 +1,499 @@
+//! Inspection of X.509 certificates.
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::ptr::NonNull;
+use std::time::{Duration, SystemTime};
+
+use crate::error::WolfCryptError;
+use crate::hash::{sha256, Sha256};
+use crate::pem::{self, PemType};
+
+/// Errors that can occur when loading a certificate.
+#[derive(Debug, thiserror::Error)]
+pub enum X509Error {
+    /// The input was not a well formed certificate in the expected encoding.
+    #[error("could not parse certificate")]
+    Parse,
+}
+
+bitflags::bitflags! {
+    /// The key usage extension of a certificate.
+    ///
+    /// Bit values are wolfSSL's `KEYUSE_*` constants.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct KeyUsage: u16 {
+        /// `digitalSignature`
+        const DIGITAL_SIGNATURE = wolfssl_sys::KEYUSE_DIGITAL_SIG as u16;
+        /// `contentCommitment` (formerly `nonRepudiation`)
+        const CONTENT_COMMITMENT = wolfssl_sys::KEYUSE_CONTENT_COMMIT as u16;
+        /// `keyEncipherment`
+        const KEY_ENCIPHERMENT = wolfssl_sys::KEYUSE_KEY_ENCIPHER as u16;
+        /// `dataEncipherment`
+        const DATA_ENCIPHERMENT = wolfssl_sys::KEYUSE_DATA_ENCIPHER as u16;
+        /// `keyAgreement`
+        const KEY_AGREEMENT = wolfssl_sys::KEYUSE_KEY_AGREE as u16;
+        /// `keyCertSign`
+        const KEY_CERT_SIGN = wolfssl_sys::KEYUSE_KEY_CERT_SIGN as u16;
+        /// `cRLSign`
+        const CRL_SIGN = wolfssl_sys::KEYUSE_CRL_SIGN as u16;
+        /// `encipherOnly`
+        const ENCIPHER_ONLY = wolfssl_sys::KEYUSE_ENCIPHER_ONLY as u16;
+        /// `decipherOnly`
+        const DECIPHER_ONLY = wolfssl_sys::KEYUSE_DECIPHER_ONLY as u16;
+    }
+}
+
+/// An owned, parsed X.509 certificate.
+#[derive(Debug)]
+pub struct X509Certificate(NonNull<wolfssl_sys::WOLFSSL_X509>);
+
+// SAFETY: The underlying `WOLFSSL_X509` is exclusively owned by this value
+// and carries no thread affinity. It is deliberately not `Sync`, since
+// wolfSSL does not document its accessors as safe for concurrent use.
+unsafe impl Send for X509Certificate {}
+
+impl X509Certificate {
+    /// Parses a DER encoded certificate.
+    pub fn from_der(der: &[u8]) -> Result<Self, X509Error> {
+        Self::load(der, wolfssl_sys::WOLFSSL_FILETYPE_ASN1 as c_int)
+    }
+
+    /// Parses the first certificate in a PEM encoded buffer.
+    pub fn from_pem(pem: &[u8]) -> Result<Self, X509Error> {
+        Self::load(pem, wolfssl_sys::WOLFSSL_FILETYPE_PEM as c_int)
+    }
+
+    fn load(buf: &[u8], format: c_int) -> Result<Self, X509Error> {
+        let len = c_int::try_from(buf.len()).map_err(|_| X509Error::Parse)?;
+
+        // SAFETY: `buf` is valid for `len` bytes, and wolfSSL copies what it
+        // needs out of it.
+        let x509 =
+            unsafe { wolfssl_sys::wolfSSL_X509_load_certificate_buffer(buf.as_ptr(), len, format) };
+
+        NonNull::new(x509).map(Self).ok_or(X509Error::Parse)
+    }
+
+    /// Returns the DER encoding of this certificate.
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut len: c_int = 0;
+
+        // SAFETY: `self.0` is a valid certificate. The returned buffer is
+        // owned by it and only borrowed for the copy below.
+        let der = unsafe { wolfssl_sys::wolfSSL_X509_get_der(self.0.as_ptr(), &mut len) };
+
+        if der.is_null() || len <= 0 {
+            return Vec::new();
+        }
+
+        // SAFETY: wolfSSL reported `len` readable bytes at `der`.
+        unsafe { std::slice::from_raw_parts(der, len as usize) }.to_vec()
+    }
+
+    /// Returns the PEM encoding of this certificate.
+    pub fn to_pem(&self) -> Result<String, WolfCryptError> {
+        pem::der_to_pem(&self.to_der(), PemType::Certificate)
+    }
+
+    /// Returns the subject distinguished name, in wolfSSL's one line format
+    /// (e.g. `/C=US/O=Example/CN=example.com`).
+    pub fn subject(&self) -> Option<String> {
+        // SAFETY: `self.0` is a valid certificate; the name is owned by it.
+        let name = unsafe { wolfssl_sys::wolfSSL_X509_get_subject_name(self.0.as_ptr()) };
+        name_to_string(name)
+    }
+
+    /// Returns the issuer distinguished name, in the same format as
+    /// [`Self::subject`].
+    pub fn issuer(&self) -> Option<String> {
+        // SAFETY: `self.0` is a valid certificate; the name is owned by it.
+        let name = unsafe { wolfssl_sys::wolfSSL_X509_get_issuer_name(self.0.as_ptr()) };
+        name_to_string(name)
+    }
+
+    /// Returns the big-endian serial number.
+    pub fn serial_number(&self) -> Option<Vec<u8>> {
+        let mut serial = vec![0u8; wolfssl_sys::EXTERNAL_SERIAL_SIZE as usize];
+        let mut len = serial.len() as c_int;
+
+        // SAFETY: `serial` is writable for `len` bytes, and wolfSSL updates
+        // `len` to the number of bytes written.
+        let ret = unsafe {
+            wolfssl_sys::wolfSSL_X509_get_serial_number(
+                self.0.as_ptr(),
+                serial.as_mut_ptr(),
+                &mut len,
+            )
+        };
+
+        if ret != wolfssl_sys::WOLFSSL_SUCCESS as c_int {
+            return None;
+        }
+
+        serial.truncate(len as usize);
+        Some(serial)
+    }
+
+    /// Returns the DNS names, IP addresses and other entries of the subject
+    /// alternative name extension, as formatted by wolfSSL.
+    ///
+    /// wolfSSL only formats IP addresses when built with
+    /// `WOLFSSL_IP_ALT_NAME`. Otherwise they come back as the raw address
+    /// bytes, lossily decoded and cut short at the first zero byte, and
+    /// should not be relied on.
+    pub fn subject_alt_names(&self) -> Vec<String> {
+        // wolfSSL walks the list with a cursor stored in the certificate,
+        // which only rewinds in builds with
+        // `WOLFSSL_MULTICIRCULATE_ALTNAMELIST`. Walk a fresh copy instead, so
+        // that every call sees every entry.
+        let Ok(copy) = Self::from_der(&self.to_der()) else {
+            return Vec::new();
+        };
+        let mut names = Vec::new();
+
+        loop {
+            // SAFETY: `copy.0` is a valid certificate, and the returned
+            // string is owned by it.
+            let name = unsafe { wolfssl_sys::wolfSSL_X509_get_next_altname(copy.0.as_ptr()) };
+            if name.is_null() {
+                break names;
+            }
+
+            // SAFETY: Non-null entries are NUL terminated strings.
+            let name = unsafe { CStr::from_ptr(name) };
+            names.push(name.to_string_lossy().into_owned());
+        }
+    }
+
+    /// Returns the key usage extension, or `None` if the certificate does not
+    /// carry one.
+    pub fn key_usage(&self) -> Option<KeyUsage> {
+        // SAFETY: `self.0` is a valid certificate.
+        let bits = unsafe { wolfssl_sys::wolfSSL_X509_get_keyUsage(self.0.as_ptr()) };
+        match bits {
+            0 => None,
+            bits => Some(KeyUsage::from_bits_truncate(bits)),
+        }
+    }
+
+    /// Returns the start of the validity period.
+    pub fn not_before(&self) -> Option<SystemTime> {
+        // SAFETY: `self.0` is a valid certificate; the time is owned by it.
+        let time = unsafe { wolfssl_sys::wolfSSL_X509_get_notBefore(self.0.as_ptr()) };
+        asn1_time_to_system_time(time)
+    }
+
+    /// Returns the end of the validity period.
+    pub fn not_after(&self) -> Option<SystemTime> {
+        // SAFETY: `self.0` is a valid certificate; the time is owned by it.
+        let time = unsafe { wolfssl_sys::wolfSSL_X509_get_notAfter(self.0.as_ptr()) };
+        asn1_time_to_system_time(time)
+    }
+
+    /// Returns the SHA-256 digest of the DER encoded certificate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if wolfCrypt fails to hash, which only happens when a FIPS
+    /// module is in an error state.
+    pub fn fingerprint_sha256(&self) -> [u8; 32] {
+        sha256(&self.to_der())
+    }
+
+    /// Returns the SHA-256 digest of the DER encoded SubjectPublicKeyInfo, as
+    /// used for HPKP-style pins.
+    pub fn spki_sha256(&self) -> Option<[u8; 32]> {
+        let der = self.to_der();
+        let der_len = der.len() as u32;
+        let mut spki_len = 0u32;
+
+        // SAFETY: `der` is valid for `der_len` bytes. A NULL output buffer
+        // asks wolfSSL for the required length only.
+        unsafe {
+            wolfssl_sys::wc_GetSubjectPubKeyInfoDerFromCert(
+                der.as_ptr(),
+                der_len,
+                std::ptr::null_mut(),
+                &mut spki_len,
+            )
+        };
+        if spki_len == 0 {
+            return None;
+        }
+
+        let mut spki = vec![0u8; spki_len as usize];
+
+        // SAFETY: `spki` is writable for `spki_len` bytes.
+        let ret = unsafe {
+            wolfssl_sys::wc_GetSubjectPubKeyInfoDerFromCert(
+                der.as_ptr(),
+                der_len,
+                spki.as_mut_ptr(),
+                &mut spki_len,
+            )
+        };
+        if ret != 0 {
+            return None;
+        }
+
+        spki.truncate(spki_len as usize);
+        Sha256::try_digest(&spki).ok()
+    }
+
+    /// Returns whether the SubjectPublicKeyInfo digest of this certificate is
+    /// one of `pins`.
+    pub fn matches_spki_pin(&self, pins: &[[u8; 32]]) -> bool {
+        self.spki_sha256()
+            .is_some_and(|digest| pins.contains(&digest))
+    }
+
+    /// Returns whether `time` falls within the validity period.
+    pub fn is_valid_at(&self, time: SystemTime) -> bool {
+        match (self.not_before(), self.not_after()) {
+            (Some(not_before), Some(not_after)) => not_before <= time && time <= not_after,
+            _ => false,
+        }
+    }
+}
+
+impl Drop for X509Certificate {
+    fn drop(&mut self) {
+        // SAFETY: We own the certificate and nothing borrows from it past
+        // this point.
+        unsafe { wolfssl_sys::wolfSSL_X509_free(self.0.as_ptr()) }
+    }
+}
+
+impl TryFrom<&[u8]> for X509Certificate {
+    type Error = X509Error;
+
+    /// Parses `buf` as PEM if it looks like PEM, otherwise as DER.
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        if buf.trim_ascii_start().starts_with(b"-----BEGIN") {
+            Self::from_pem(buf)
+        } else {
+            Self::from_der(buf)
+        }
+    }
+}
+
+fn name_to_string(name: *mut wolfssl_sys::WOLFSSL_X509_NAME) -> Option<String> {
+    if name.is_null() {
+        return None;
+    }
+
+    // SAFETY: `name` is valid. Passing a NULL buffer asks wolfSSL to
+    // allocate one, which we free below.
+    let raw: *mut c_char =
+        unsafe { wolfssl_sys::wolfSSL_X509_NAME_oneline(name, std::ptr::null_mut(), 0) };
+    if raw.is_null() {
+        return None;
+    }
+
+    // SAFETY: wolfSSL returned a NUL terminated string.
+    let s = unsafe { CStr::from_ptr(raw) }
+        .to_string_lossy()
+        .into_owned();
+
+    // SAFETY: `raw` was allocated by wolfSSL and is not used again.
+    unsafe { wolfssl_sys::wolfSSL_Free(raw.cast()) };
+
+    Some(s)
+}
+
+fn asn1_time_to_system_time(time: *const wolfssl_sys::WOLFSSL_ASN1_TIME) -> Option<SystemTime> {
+    if time.is_null() {
+        return None;
+    }
+
+    // SAFETY: `tm` is plain old data, for which all zeroes is a valid value.
+    let mut tm: wolfssl_sys::tm = unsafe { std::mem::zeroed() };
+
+    // SAFETY: `time` is valid and `tm` is writable.
+    let ret = unsafe { wolfssl_sys::wolfSSL_ASN1_TIME_to_tm(time, &mut tm) };
+    if ret != wolfssl_sys::WOLFSSL_SUCCESS as c_int {
+        return None;
+    }
+
+    let days = days_from_civil(
+        i64::from(tm.tm_year) + 1900,
+        i64::from(tm.tm_mon) + 1,
+        i64::from(tm.tm_mday),
+    );
+    let secs = days * 86_400
+        + i64::from(tm.tm_hour) * 3_600
+        + i64::from(tm.tm_min) * 60
+        + i64::from(tm.tm_sec);
+
+    match u64::try_from(secs) {
+        Ok(secs) => SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(secs)),
+        Err(_) => SystemTime::UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs())),
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A self-signed P-256 certificate for `example.com` and
+    /// `www.example.com`.
+    const CERT: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBrDCCAVKgAwIBAgIUfiC/lJH9i8grRjN4TogdtDERDCcwCgYIKoZIzj0EAwIw
+FjEUMBIGA1UEAwwLZXhhbXBsZS5jb20wIBcNMjYxMDE1MDM1ODAyWhgPMjEyNjA5
+MjEwMzU4MDJaMBYxFDASBgNVBAMMC2V4YW1wbGUuY29tMFkwEwYHKoZIzj0CAQYI
+KoZIzj0DAQcDQgAEqYJ+ULzHHXRdPssWmP6wpcBsFHBF2nvhAoEVIUeq700OqJ1D
+qHww0JlNp69lzlme5UaO8qEI8zVns5T9p3GYj6N8MHowHQYDVR0OBBYEFMpxtnE7
+nB3j8QmspkZ3fW95VPIvMB8GA1UdIwQYMBaAFMpxtnE7nB3j8QmspkZ3fW95VPIv
+MA8GA1UdEwEB/wQFMAMBAf8wJwYDVR0RBCAwHoILZXhhbXBsZS5jb22CD3d3dy5l
+eGFtcGxlLmNvbTAKBggqhkjOPQQDAgNIADBFAiA6tV0pmqAF45XwnmLCX0Sza+EX
+Cnp43+gE+i5nktdpCgIhAPo3pnyX0m96Zybi2A4SAdU9f05eFStaqKB7XkCzQXvJ
+-----END CERTIFICATE-----
+";
+
+    /// A self-signed CA certificate for the same key as [`CERT`], with a
+    /// critical key usage extension.
+    const CA_CERT: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBmTCCAT+gAwIBAgIUSxwKWwNSE9QiTgMaDxn+Ujo2FeUwCgYIKoZIzj0EAwIw
+GTEXMBUGA1UEAwwOY2EuZXhhbXBsZS5jb20wIBcNMjYxMDE1MDQxMDU2WhgPMjEy
+NjA5MjEwNDEwNTZaMBkxFzAVBgNVBAMMDmNhLmV4YW1wbGUuY29tMFkwEwYHKoZI
+zj0CAQYIKoZIzj0DAQcDQgAEqYJ+ULzHHXRdPssWmP6wpcBsFHBF2nvhAoEVIUeq
+700OqJ1DqHww0JlNp69lzlme5UaO8qEI8zVns5T9p3GYj6NjMGEwHQYDVR0OBBYE
+FMpxtnE7nB3j8QmspkZ3fW95VPIvMB8GA1UdIwQYMBaAFMpxtnE7nB3j8QmspkZ3
+fW95VPIvMA4GA1UdDwEB/wQEAwIBhjAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49
+BAMCA0gAMEUCIQD0EIQ7H0xi3vnfnrXaAA13LEMOBm19Eb3/iT7z+w5UGQIgRNBa
+kxBwFNbXJhyfsvvAOPK/MwUWbWtXGFJHqUWr0MU=
+-----END CERTIFICATE-----
+";
+
+    /// `openssl x509 -outform der | sha256sum` of [`CERT`].
+    const FINGERPRINT: &str = "0e39fbb1fb14c30bbb7c7275cb7465539531101c4a43d1e58e1dcf733f39c852";
+
+    /// `openssl x509 -pubkey | openssl pkey -pubin -outform der | sha256sum`
+    /// of [`CERT`], and of [`CA_CERT`] which shares its key.
+    const SPKI_PIN: &str = "124aa2756b17ab9875940b98bc6b6541d6f86279d0d16b3cfc9479c1fd3376a8";
+
+    fn digest(hex: &str) -> [u8; 32] {
+        hex::decode(hex).unwrap().try_into().unwrap()
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn parses_pem_and_der() {
+        let cert = X509Certificate::from_pem(CERT.as_bytes()).unwrap();
+        let der = cert.to_der();
+        let reparsed = X509Certificate::from_der(&der).unwrap();
+
+        assert_eq!(reparsed.to_der(), der);
+        assert_eq!(cert.subject().as_deref(), Some("/CN=example.com"));
+        assert_eq!(cert.issuer(), cert.subject());
+    }
+
+    #[test]
+    fn subject_alt_names_are_repeatable() {
+        let cert = X509Certificate::from_pem(CERT.as_bytes()).unwrap();
+
+        for _ in 0..2 {
+            let mut names = cert.subject_alt_names();
+            names.sort();
+            assert_eq!(names, ["example.com", "www.example.com"]);
+        }
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(X509Certificate::from_der(b"not a certificate").is_err());
+        assert!(X509Certificate::from_pem(b"not a certificate").is_err());
+    }
+
+    #[test]
+    fn serial_number() {
+        let cert = X509Certificate::from_pem(CERT.as_bytes()).unwrap();
+
+        assert_eq!(
+            hex::encode(cert.serial_number().unwrap()),
+            "7e20bf9491fd8bc82b4633784e881db431110c27"
+        );
+    }
+
+    #[test]
+    fn key_usage() {
+        let cert = X509Certificate::from_pem(CERT.as_bytes()).unwrap();
+        let ca = X509Certificate::from_pem(CA_CERT.as_bytes()).unwrap();
+
+        assert_eq!(cert.key_usage(), None);
+        assert_eq!(
+            ca.key_usage(),
+            Some(KeyUsage::DIGITAL_SIGNATURE | KeyUsage::KEY_CERT_SIGN | KeyUsage::CRL_SIGN)
+        );
+    }
+
+    #[test]
+    fn validity_period() {
+        let cert = X509Certificate::from_pem(CERT.as_bytes()).unwrap();
+
+        // 2026-10-15T03:58:02Z (UTCTime) and 2126-09-21T03:58:02Z
+        // (GeneralizedTime).
+        let not_before = 1_792_036_682;
+        let not_after = 4_945_636_682;
+        assert_eq!(cert.not_before(), Some(at(not_before)));
+        assert_eq!(cert.not_after(), Some(at(not_after)));
+
+        assert!(!cert.is_valid_at(at(not_before - 1)));
+        assert!(cert.is_valid_at(at(not_before)));
+        assert!(cert.is_valid_at(at(not_after)));
+        assert!(!cert.is_valid_at(at(not_after + 1)));
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(days_from_civil(2024, 2, 29), 19_782);
+        assert_eq!(days_from_civil(2100, 3, 1), 47_541);
+        assert_eq!(days_from_civil(1600, 1, 1), -135_140);
+    }
+
+    #[test]
+    fn digests() {
+        let cert = X509Certificate::from_pem(CERT.as_bytes()).unwrap();
+        let ca = X509Certificate::from_pem(CA_CERT.as_bytes()).unwrap();
+
+        assert_eq!(cert.fingerprint_sha256(), digest(FINGERPRINT));
+        assert_eq!(cert.spki_sha256(), Some(digest(SPKI_PIN)));
+
+        assert_ne!(ca.fingerprint_sha256(), digest(FINGERPRINT));
+        assert_eq!(ca.spki_sha256(), Some(digest(SPKI_PIN)));
+    }
+
+    #[test]
+    fn spki_pins() {
+        let cert = X509Certificate::from_pem(CERT.as_bytes()).unwrap();
+        let pin = digest(SPKI_PIN);
+        let mut other = pin;
+        other[31] ^= 1;
+
+        assert!(cert.matches_spki_pin(&[pin]));
+        assert!(cert.matches_spki_pin(&[other, pin]));
+        assert!(!cert.matches_spki_pin(&[other]));
+        assert!(!cert.matches_spki_pin(&[digest(FINGERPRINT)]));
+        assert!(!cert.matches_spki_pin(&[]));
+    }
+}